@@ -0,0 +1,155 @@
+///
+/// Pluggable cipher backend abstracting the ciphertext types and homomorphic operations the
+/// federated protocol uses, so correctness can be tested against a `Cleartext` backend while
+/// production runs against a real `Tfhe` backend instead of branching on plaintext values
+/// throughout `organization_struct`. This is the seam the commented-out TFHE code in
+/// `PrivateKeyOrganization`/`PublicKeyOrganization` was written around.
+///
+use tfhe::prelude::*;
+use tfhe::{ClientKey, FheBool, FheUint16, FheUint64};
+
+/// An encoded activity, encrypted or not depending on the backend in use.
+#[derive(Clone)]
+pub enum ActivityCt {
+    Cleartext(u16),
+    Tfhe(Box<FheUint16>),
+}
+
+/// A timestamp, encrypted or not depending on the backend in use.
+#[derive(Clone)]
+pub enum TimestampCt {
+    Cleartext(u64),
+    Tfhe(Box<FheUint64>),
+}
+
+/// A boolean, encrypted or not depending on the backend in use.
+#[derive(Clone)]
+pub enum BoolCt {
+    Cleartext(bool),
+    Tfhe(Box<FheBool>),
+}
+
+///
+/// Abstracts the ciphertext types and operations the federated protocol needs: encrypt/decrypt,
+/// homomorphic `le`/`eq`, and `select` (cmux). `PrivateKeyOrganization` holds one of these behind a
+/// `Box<dyn CipherBackend>` chosen at construction time; `PublicKeyOrganization` holds none and
+/// uses a local `Cleartext` instance in `sanitize_sample_encryptions`.
+///
+pub trait CipherBackend {
+    fn encrypt_activity(&self, value: u16, key: &ClientKey) -> ActivityCt;
+    fn encrypt_timestamp(&self, value: u64, key: &ClientKey) -> TimestampCt;
+    fn encrypt_true(&self, key: &ClientKey) -> BoolCt;
+    fn decrypt_activity(&self, value: &ActivityCt, key: &ClientKey) -> u16;
+    fn le(&self, a: &TimestampCt, b: &TimestampCt) -> BoolCt;
+    fn eq_activity(&self, a: &ActivityCt, value: u16) -> BoolCt;
+    fn select(&self, condition: &BoolCt, if_true: &ActivityCt, if_false: &ActivityCt) -> ActivityCt;
+}
+
+/// The debug backend: every operation runs on the plaintext value directly, matching this crate's
+/// existing `debug` passthrough behavior.
+pub struct Cleartext;
+
+impl CipherBackend for Cleartext {
+    fn encrypt_activity(&self, value: u16, _key: &ClientKey) -> ActivityCt {
+        ActivityCt::Cleartext(value)
+    }
+
+    fn encrypt_timestamp(&self, value: u64, _key: &ClientKey) -> TimestampCt {
+        TimestampCt::Cleartext(value)
+    }
+
+    fn encrypt_true(&self, _key: &ClientKey) -> BoolCt {
+        BoolCt::Cleartext(true)
+    }
+
+    fn decrypt_activity(&self, value: &ActivityCt, _key: &ClientKey) -> u16 {
+        match value {
+            ActivityCt::Cleartext(v) => *v,
+            ActivityCt::Tfhe(_) => panic!("Cleartext backend received a Tfhe ciphertext"),
+        }
+    }
+
+    fn le(&self, a: &TimestampCt, b: &TimestampCt) -> BoolCt {
+        match (a, b) {
+            (TimestampCt::Cleartext(a), TimestampCt::Cleartext(b)) => BoolCt::Cleartext(a <= b),
+            _ => panic!("Cleartext backend received a Tfhe ciphertext"),
+        }
+    }
+
+    fn eq_activity(&self, a: &ActivityCt, value: u16) -> BoolCt {
+        match a {
+            ActivityCt::Cleartext(a) => BoolCt::Cleartext(*a == value),
+            ActivityCt::Tfhe(_) => panic!("Cleartext backend received a Tfhe ciphertext"),
+        }
+    }
+
+    fn select(&self, condition: &BoolCt, if_true: &ActivityCt, if_false: &ActivityCt) -> ActivityCt {
+        match (condition, if_true, if_false) {
+            (BoolCt::Cleartext(cond), ActivityCt::Cleartext(t), ActivityCt::Cleartext(f)) => {
+                ActivityCt::Cleartext(if *cond { *t } else { *f })
+            }
+            _ => panic!("Cleartext backend received a Tfhe ciphertext"),
+        }
+    }
+}
+
+/// The production backend: every operation runs fully homomorphically over TFHE ciphertexts.
+pub struct Tfhe;
+
+impl CipherBackend for Tfhe {
+    fn encrypt_activity(&self, value: u16, key: &ClientKey) -> ActivityCt {
+        ActivityCt::Tfhe(Box::new(FheUint16::encrypt(value, key)))
+    }
+
+    fn encrypt_timestamp(&self, value: u64, key: &ClientKey) -> TimestampCt {
+        TimestampCt::Tfhe(Box::new(FheUint64::encrypt(value, key)))
+    }
+
+    fn encrypt_true(&self, key: &ClientKey) -> BoolCt {
+        BoolCt::Tfhe(Box::new(FheBool::encrypt(true, key)))
+    }
+
+    fn decrypt_activity(&self, value: &ActivityCt, key: &ClientKey) -> u16 {
+        match value {
+            ActivityCt::Tfhe(ct) => ct.decrypt(key),
+            ActivityCt::Cleartext(_) => panic!("Tfhe backend received a cleartext value"),
+        }
+    }
+
+    fn le(&self, a: &TimestampCt, b: &TimestampCt) -> BoolCt {
+        match (a, b) {
+            (TimestampCt::Tfhe(a), TimestampCt::Tfhe(b)) => {
+                BoolCt::Tfhe(Box::new(a.as_ref().le(b.as_ref())))
+            }
+            _ => panic!("Tfhe backend received a cleartext value"),
+        }
+    }
+
+    fn eq_activity(&self, a: &ActivityCt, value: u16) -> BoolCt {
+        match a {
+            ActivityCt::Tfhe(a) => BoolCt::Tfhe(Box::new(a.as_ref().eq(value))),
+            ActivityCt::Cleartext(_) => panic!("Tfhe backend received a cleartext value"),
+        }
+    }
+
+    fn select(&self, condition: &BoolCt, if_true: &ActivityCt, if_false: &ActivityCt) -> ActivityCt {
+        match (condition, if_true, if_false) {
+            (BoolCt::Tfhe(cond), ActivityCt::Tfhe(t), ActivityCt::Tfhe(f)) => {
+                ActivityCt::Tfhe(Box::new(cond.as_ref().select(t.as_ref(), f.as_ref())))
+            }
+            _ => panic!("Tfhe backend received a cleartext value"),
+        }
+    }
+}
+
+///
+/// Picks the backend matching this crate's existing `debug` flag: `Cleartext` for debug runs,
+/// `Tfhe` for production runs.
+///
+pub fn backend_for(debug: bool) -> Box<dyn CipherBackend + Send + Sync> {
+    if debug {
+        Box::new(Cleartext)
+    } else {
+        Box::new(Tfhe)
+    }
+}
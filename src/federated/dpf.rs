@@ -0,0 +1,164 @@
+///
+/// Distributed point functions (DPF), the primitive behind this module's oblivious PSI: a point
+/// function `f_{alpha,beta}` (equal to `beta` at `alpha`, `0` elsewhere) is secret-shared into two
+/// keys via a GGM-style binary tree of PRG expansions. At the root each party holds a seed and a
+/// control bit (`0` and `1`). Descending level `i`, each party expands its seed into
+/// `(sL, tL, sR, tR)` and applies a public correction word `CW_i` only when its control bit is set,
+/// chosen so that on the bit-path of `alpha` the two parties' seeds stay pseudorandomly different
+/// while off-path they collapse to identical values (and therefore cancel under XOR), with a final
+/// correction word encoding `beta`. `Eval(b, key, x)` walks `x`'s bits and the two parties' outputs
+/// sum (XOR, since the output is a single bit here) to `f(x)`.
+///
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+
+/// Bits in the DPF's input domain. Case IDs are hashed into this many bits before being used as a
+/// DPF index, bounding the domain to `2^DOMAIN_BITS` points.
+pub const DOMAIN_BITS: u32 = 24;
+
+type Seed = u64;
+
+fn prg_branch(seed: Seed, branch: u8) -> (Seed, bool) {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    branch.hash(&mut hasher);
+    let digest = hasher.finish();
+    (digest, digest & 1 == 1)
+}
+
+/// Expands a seed into the GGM tree's left and right children: seed and control bit each.
+fn expand(seed: Seed) -> (Seed, bool, Seed, bool) {
+    let (sl, tl) = prg_branch(seed, 0);
+    let (sr, tr) = prg_branch(seed, 1);
+    (sl, tl, sr, tr)
+}
+
+fn bit_at(x: u32, level: u32) -> bool {
+    (x >> (DOMAIN_BITS - 1 - level)) & 1 == 1
+}
+
+#[derive(Clone, Copy)]
+struct CorrectionWord {
+    seed: Seed,
+    bit_left: bool,
+    bit_right: bool,
+}
+
+/// A DPF key held by one of the two parties; `Eval` walks it level by level to test membership.
+#[derive(Clone)]
+pub struct DpfKey {
+    root_seed: Seed,
+    root_control_bit: bool,
+    correction_words: Vec<CorrectionWord>,
+    final_correction: bool,
+}
+
+///
+/// Secret-shares the point function that is `true` at `alpha` (an index in `0..2^DOMAIN_BITS`) and
+/// `false` everywhere else, returning the two parties' DPF keys.
+///
+pub fn gen(alpha: u32) -> (DpfKey, DpfKey) {
+    let root_seed_0: Seed = rand::random();
+    let root_seed_1: Seed = rand::random();
+
+    let mut s0 = root_seed_0;
+    let mut s1 = root_seed_1;
+    let mut t0 = false;
+    let mut t1 = true;
+
+    let mut correction_words = Vec::with_capacity(DOMAIN_BITS as usize);
+
+    for level in 0..DOMAIN_BITS {
+        let (s0l, t0l, s0r, t0r) = expand(s0);
+        let (s1l, t1l, s1r, t1r) = expand(s1);
+        let on_path_right = bit_at(alpha, level);
+
+        // The off-path branch is corrected so the two parties' seeds collapse there; the on-path
+        // branch's control bit is flipped so the parties keep diverging along alpha's path.
+        let (seed_cw, bit_left_cw, bit_right_cw) = if on_path_right {
+            (s0l ^ s1l, t0l ^ t1l, t0r ^ t1r ^ true)
+        } else {
+            (s0r ^ s1r, t0l ^ t1l ^ true, t0r ^ t1r)
+        };
+        correction_words.push(CorrectionWord {
+            seed: seed_cw,
+            bit_left: bit_left_cw,
+            bit_right: bit_right_cw,
+        });
+
+        let apply = |t: bool, seed: Seed, bit: bool, cw_seed: Seed, cw_bit: bool| -> (Seed, bool) {
+            let corrected_seed = if t { seed ^ cw_seed } else { seed };
+            let corrected_bit = bit ^ (t && cw_bit);
+            (corrected_seed, corrected_bit)
+        };
+
+        let (s0_next, t0_next) = if on_path_right {
+            apply(t0, s0r, t0r, seed_cw, bit_right_cw)
+        } else {
+            apply(t0, s0l, t0l, seed_cw, bit_left_cw)
+        };
+        let (s1_next, t1_next) = if on_path_right {
+            apply(t1, s1r, t1r, seed_cw, bit_right_cw)
+        } else {
+            apply(t1, s1l, t1l, seed_cw, bit_left_cw)
+        };
+
+        s0 = s0_next;
+        s1 = s1_next;
+        t0 = t0_next;
+        t1 = t1_next;
+    }
+
+    // Chooses the final correction bit so that LSB(s0) XOR LSB(s1) XOR ((t0 XOR t1) &
+    // final_correction), the XOR of both parties' `eval` outputs, equals `true` at the path just
+    // walked (alpha).
+    let final_correction = (s0 & 1 == 1) ^ (s1 & 1 == 1) ^ true;
+
+    (
+        DpfKey {
+            root_seed: root_seed_0,
+            root_control_bit: false,
+            correction_words: correction_words.clone(),
+            final_correction,
+        },
+        DpfKey {
+            root_seed: root_seed_1,
+            root_control_bit: true,
+            correction_words,
+            final_correction,
+        },
+    )
+}
+
+///
+/// Evaluates a single party's share of the point function at `x`.
+///
+pub fn eval(key: &DpfKey, x: u32) -> bool {
+    let mut seed = key.root_seed;
+    let mut control_bit = key.root_control_bit;
+
+    for level in 0..DOMAIN_BITS {
+        let (sl, tl, sr, tr) = expand(seed);
+        let cw = &key.correction_words[level as usize];
+
+        let (next_seed, next_bit, cw_seed, cw_bit) = if bit_at(x, level) {
+            (sr, tr, cw.seed, cw.bit_right)
+        } else {
+            (sl, tl, cw.seed, cw.bit_left)
+        };
+
+        seed = if control_bit { next_seed ^ cw_seed } else { next_seed };
+        control_bit = next_bit ^ (control_bit && cw_bit);
+    }
+
+    (seed & 1 == 1) ^ (control_bit && key.final_correction)
+}
+
+///
+/// Sums (XORs) a party's share of the point function over every element of `indices`, the
+/// building block for testing whether `alpha` is a member of a set without walking it one point at
+/// a time outside this helper.
+///
+pub fn eval_sum(key: &DpfKey, indices: &[u32]) -> bool {
+    indices.iter().fold(false, |acc, &x| acc ^ eval(key, x))
+}
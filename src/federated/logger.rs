@@ -0,0 +1,82 @@
+///
+/// Pluggable observability hooks for the federated discovery pipeline, mirroring the pattern of
+/// injecting a logger into each phase of a search/solver algorithm instead of hardcoding tracing
+/// into the algorithm itself. Passing `&mut dyn DiscoveryLogger` through `organization_communication`
+/// and `utils::recalculate_activity_counts` lets callers observe how activity counts and
+/// directly-follows relations evolve across merge rounds, and dump intermediate graphs for
+/// debugging, without patching the core pipeline functions.
+///
+use process_mining::dfg::DirectlyFollowsGraph;
+
+///
+/// Callback hooks a federated discovery run calls back into as it progresses. Every hook has a
+/// no-op default, so implementers only override the ones they care about.
+///
+pub trait DiscoveryLogger {
+    ///
+    /// Called when a participating site's own local directly-follows graph becomes available,
+    /// before it is folded into any merge.
+    ///
+    fn on_local_dfg(&mut self, site_id: &str, dfg: &DirectlyFollowsGraph) {
+        let _ = (site_id, dfg);
+    }
+
+    ///
+    /// Called after folding another site's or round's edges into the running merged graph.
+    ///
+    fn on_merge_step(&mut self, dfg: &DirectlyFollowsGraph) {
+        let _ = dfg;
+    }
+
+    ///
+    /// Called after activity counts have been (re)computed, e.g. following
+    /// `utils::recalculate_activity_counts` or a sequence of `utils::apply_df_delta` calls.
+    ///
+    fn on_recalculate_counts(&mut self, dfg: &DirectlyFollowsGraph) {
+        let _ = dfg;
+    }
+
+    ///
+    /// Called once a federated run has produced its final directly-follows graph.
+    ///
+    fn on_final(&mut self, dfg: &DirectlyFollowsGraph) {
+        let _ = dfg;
+    }
+}
+
+///
+/// No-op `DiscoveryLogger`, the default when nobody wants tracing, so the pipeline can always take
+/// `&mut dyn DiscoveryLogger` without special-casing "no logger" at every call site.
+///
+pub struct DefaultLogger;
+
+impl DiscoveryLogger for DefaultLogger {}
+
+///
+/// A `DiscoveryLogger` that snapshots every graph it's handed as JSON (via `to_json`, the same
+/// serialization the CLI's `discover` subcommand writes), tagged by which hook produced it, so
+/// callers can inspect how counts and relations evolved across a run instead of only seeing the
+/// final graph.
+///
+#[derive(Default)]
+pub struct CollectingLogger {
+    pub snapshots: Vec<(String, String)>,
+}
+
+impl DiscoveryLogger for CollectingLogger {
+    fn on_local_dfg(&mut self, site_id: &str, dfg: &DirectlyFollowsGraph) {
+        self.snapshots.push((format!("local_dfg:{}", site_id), dfg.to_json()));
+    }
+
+    fn on_merge_step(&mut self, dfg: &DirectlyFollowsGraph) {
+        self.snapshots.push(("merge_step".to_string(), dfg.to_json()));
+    }
+
+    fn on_recalculate_counts(&mut self, dfg: &DirectlyFollowsGraph) {
+        self.snapshots.push(("recalculate_counts".to_string(), dfg.to_json()));
+    }
+
+    fn on_final(&mut self, dfg: &DirectlyFollowsGraph) {
+        self.snapshots.push(("final".to_string(), dfg.to_json()));
+    }
+}
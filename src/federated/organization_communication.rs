@@ -1,11 +1,27 @@
+use crate::federated::communicator::{Communicator, Phase, PhaseStats};
+use crate::federated::logger::DiscoveryLogger;
 use crate::federated::organization_struct::{PrivateKeyOrganization, PublicKeyOrganization};
+use crate::federated::psi;
+use crate::federated::transport::ProtocolMessage;
 use crate::federated::utils;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use process_mining::dfg::DirectlyFollowsGraph;
 use std::collections::{HashMap, HashSet};
+use std::io;
 use std::time::Instant;
 use tfhe::{FheBool, FheUint16, FheUint32, FheUint64, ServerKey};
 
+///
+/// Final tallies of homomorphic operations a [`communicate`] run performed, returned alongside the
+/// graph so callers (e.g. the CLI's `discover` subcommand) can report them without having to parse
+/// `communicate`'s log output.
+///
+pub struct HomomorphicCounters {
+    pub case_id_hom_comparisons: u64,
+    pub timestamp_hom_comparisons: u64,
+    pub selection_hom_comparisons: u64,
+}
+
 /// The protocol for the federated computation of a directly-follows graph between two organizations
 ///
 /// # Arguments
@@ -13,14 +29,30 @@ use tfhe::{FheBool, FheUint16, FheUint32, FheUint64, ServerKey};
 /// * `org_a`: A private key-owning organization
 /// * `org_b`: A public key-owning organization.
 /// * `window_size`: A window size to reduce the number of traces to be computed in B.
+/// * `use_psi`: Whether to privately pre-align case IDs via DH-PSI before any homomorphic work,
+///   instead of letting every org A case ID flow into encryption unfiltered.
+/// * `use_oblivious_accumulation`: Whether to fold decrypted edges into the DFG via
+///   [`PrivateKeyOrganization::accumulate_decrypted_edges_oblivious`]'s DPF-keyed writes instead of
+///   the cheap direct `HashMap` path, hiding which edges were found from anything observing the
+///   accumulation's memory-access pattern.
+/// * `min_edge_frequency`: Drops any directly-follows edge seen fewer than this many times from
+///   the output graph (`0` keeps every edge), via
+///   [`PrivateKeyOrganization::evaluate_decrypted_edges_to_dfg_with_threshold`].
+/// * `logger`: Observability hooks called back into as the merge progresses; pass
+///   `&mut crate::federated::logger::DefaultLogger` for a no-op.
 ///
-/// Returns: DirectlyFollowsGraph The directly-follows graph of the federate computation.
+/// Returns: DirectlyFollowsGraph The directly-follows graph of the federate computation, plus the
+/// [`HomomorphicCounters`] tallied along the way.
 ///
 pub fn communicate<'a>(
     org_a: &'a mut PrivateKeyOrganization,
     org_b: &'a mut PublicKeyOrganization,
     window_size: usize,
-) -> DirectlyFollowsGraph<'a> {
+    use_psi: bool,
+    use_oblivious_accumulation: bool,
+    min_edge_frequency: u64,
+    logger: &mut dyn DiscoveryLogger,
+) -> (DirectlyFollowsGraph<'a>, HomomorphicCounters) {
     // Introduce variables to keep track of homomorphic operations
     let mut case_id_hom_comparisons: u64 = 0;
     let mut timestamp_hom_comparisons: u64 = 0;
@@ -48,10 +80,24 @@ pub fn communicate<'a>(
         time_elapsed_encoding_agreement
     );
 
+    println!("Determine shared case IDs");
+    let time_start_psi = Instant::now();
+    let shared_case_ids: HashSet<String> = if use_psi {
+        psi::intersect_case_ids(&org_a.get_all_case_ids(), &org_b.get_all_case_ids())
+    } else {
+        org_a.get_all_case_ids().into_iter().collect()
+    };
+    let time_elapsed_psi = time_start_psi.elapsed().as_millis();
+    println!(
+        "Case ID alignment ({} shared) - Time elapsed is {}ms",
+        shared_case_ids.len(),
+        time_elapsed_psi
+    );
+
     println!("Encrypt & encode data for organization A");
     let time_start_encrypt_org_a = Instant::now();
-    let org_a_encrypted_data: Vec<(u64, u16, u64)> =
-        org_a.encrypt_all_data();
+    let org_a_encrypted_data: HashMap<String, (Vec<u16>, Vec<u64>)> =
+        org_a.encrypt_all_data(&shared_case_ids);
     org_b.set_foreign_case_to_trace(org_a_encrypted_data);
     let time_elapsed_encrypt_org_a = time_start_encrypt_org_a.elapsed().as_millis();
     println!(
@@ -92,9 +138,11 @@ pub fn communicate<'a>(
     let time_start_edge_finding = Instant::now();
 
 
+    org_b.compute_all_case_names();
     let org_b_secrets: Vec<(u16, u16)> = org_b.find_all_secrets(
+        0,
+        org_b.get_cases_len(),
         &progress_cases,
-        &mut case_id_hom_comparisons,
         &mut timestamp_hom_comparisons,
         &mut selection_hom_comparisons,
     );
@@ -110,9 +158,26 @@ pub fn communicate<'a>(
 
     println!("Transform the computed and decrypted edges to a directly-follows graph");
     let time_start_computing_dfg = Instant::now();
-    let mut graph: DirectlyFollowsGraph = org_a.evaluate_decrypted_edges_to_dfg(decrypted_edges);
+    let mut graph: DirectlyFollowsGraph = if use_oblivious_accumulation {
+        let graph = org_a.evaluate_decrypted_edges_to_dfg_oblivious(decrypted_edges);
+        if min_edge_frequency > 0 {
+            // The oblivious path tallies frequencies via `ObliviousEdgeAccumulator` rather than
+            // `edge_frequencies`, so thresholding here is a post-hoc filter on the resulting
+            // relations instead of going through `accumulate_edge_frequencies` directly.
+            let mut graph = graph;
+            graph
+                .directly_follows_relations
+                .retain(|_, freq| *freq as u64 >= min_edge_frequency);
+            graph
+        } else {
+            graph
+        }
+    } else {
+        org_a.evaluate_decrypted_edges_to_dfg_with_threshold(decrypted_edges, min_edge_frequency)
+    };
+    logger.on_merge_step(&graph);
 
-    utils::recalculate_activity_counts(&mut graph);
+    utils::recalculate_activity_counts(&mut graph, logger);
 
     graph.directly_follows_relations = graph
         .directly_follows_relations
@@ -151,5 +216,337 @@ pub fn communicate<'a>(
         selection_hom_comparisons
     );
 
+    logger.on_final(&graph);
+
+    (
+        graph,
+        HomomorphicCounters {
+            case_id_hom_comparisons,
+            timestamp_hom_comparisons,
+            selection_hom_comparisons,
+        },
+    )
+}
+
+/// The protocol for the federated computation of a directly-follows graph across a coordinator and an
+/// arbitrary number of participating organizations, generalizing [`communicate`] from strict two-party
+/// to N-party federation.
+///
+/// Every participant's activities are folded into one agreed `activity_to_pos` map before any
+/// encryption happens (so cross-organization handoffs stay detectable no matter which party contributed
+/// which activity), and all parties encrypt their traces under the coordinator's broadcast `ServerKey`.
+/// The first party then acts as merge leader: it runs one `find_all_secrets_multi` pass that k-way
+/// merges, per case, the coordinator's trace with every party's own trace, and the resulting edges
+/// are decrypted once and folded into a single merged `DirectlyFollowsGraph`.
+///
+/// # Arguments
+///
+/// * `coordinator`: The private key-owning organization driving the protocol.
+/// * `parties`: The public key-owning organizations participating in the federation.
+/// * `window_size`: Does not limit the merge: every case is processed in one
+///   `find_all_secrets_multi` pass whatever its value.
+/// * `logger`: Observability hooks called back into as the merge progresses; pass
+///   `&mut crate::federated::logger::DefaultLogger` for a no-op.
+///
+/// Returns: DirectlyFollowsGraph The directly-follows graph merged across all organizations.
+///
+pub fn communicate_multi<'a>(
+    coordinator: &'a mut PrivateKeyOrganization,
+    parties: &'a mut Vec<PublicKeyOrganization>,
+    window_size: usize,
+    logger: &mut dyn DiscoveryLogger,
+) -> DirectlyFollowsGraph<'a> {
+    let mut case_id_hom_comparisons: u64 = 0;
+    let mut timestamp_hom_comparisons: u64 = 0;
+    let mut selection_hom_comparisons: u64 = 0;
+
+    println!(
+        "Start multi-party communication across {} organizations",
+        parties.len()
+    );
+
+    println!("Broadcast server key");
+    let server_key: ServerKey = coordinator.get_server_key();
+    parties
+        .iter_mut()
+        .for_each(|party| party.set_server_key(server_key.clone()));
+
+    println!("Agree on activity encoding across all organizations");
+    let time_start_enconding_agreement = Instant::now();
+    let mut all_foreign_activities: HashSet<String> = HashSet::new();
+    parties
+        .iter()
+        .for_each(|party| all_foreign_activities.extend(party.find_activities()));
+    let agreed_activity_to_pos: HashMap<String, usize> =
+        coordinator.update_with_foreign_activities(all_foreign_activities);
+    let mut sample_encryptions: HashMap<u16, u16> = coordinator.provide_sample_encryptions();
+
+    parties.iter_mut().for_each(|party| {
+        party.sanitize_sample_encryptions(&mut sample_encryptions);
+        party.set_activity_to_pos(agreed_activity_to_pos.clone(), &sample_encryptions);
+    });
+    let time_elapsed_encoding_agreement = time_start_enconding_agreement.elapsed().as_millis();
+    println!(
+        "Encoding agreement - Time elapsed is {}ms",
+        time_elapsed_encoding_agreement
+    );
+
+    println!("Encrypt coordinator data and distribute it to all organizations");
+    let time_start_encrypt_coordinator = Instant::now();
+    let shared_case_ids: HashSet<String> = coordinator.get_all_case_ids().into_iter().collect();
+    let coordinator_encrypted_data: HashMap<String, (Vec<u16>, Vec<u64>)> =
+        coordinator.encrypt_all_data(&shared_case_ids);
+    parties.iter_mut().for_each(|party| {
+        party.set_foreign_case_to_trace(coordinator_encrypted_data.clone());
+        party.compute_all_case_names();
+        party.encrypt_all_data(&sample_encryptions);
+    });
+    let time_elapsed_encrypt_coordinator = time_start_encrypt_coordinator.elapsed().as_millis();
+    println!(
+        "Encrypting coordinator data - Time elapsed is {}ms",
+        time_elapsed_encrypt_coordinator
+    );
+
+    println!("Merge every organization's traces per case and decrypt the resulting edges");
+    let time_start_edge_finding = Instant::now();
+    let mut graph = DirectlyFollowsGraph::default();
+    coordinator.seed_dfg_activities(&mut graph);
+
+    // Every party's own trace for a case is folded into one k-way merge instead of running a
+    // separate two-party merge per party and accumulating the results, so a case shared by several
+    // parties gets a single, correctly-ordered merge of all of its contributors instead of being
+    // chronologically spliced back together from independent pairwise merges.
+    let own_traces_by_party: Vec<HashMap<String, (Vec<u16>, Vec<u64>)>> = parties
+        .iter()
+        .map(|party| party.get_own_case_to_trace().clone())
+        .collect();
+
+    let mut foreign_cases_to_traces: HashMap<String, Vec<(Vec<u16>, Vec<u64>)>> = HashMap::new();
+    for (case_name, trace) in coordinator_encrypted_data.iter() {
+        foreign_cases_to_traces
+            .entry(case_name.clone())
+            .or_default()
+            .push(trace.clone());
+    }
+    for own_traces in own_traces_by_party.iter().skip(1) {
+        for (case_name, trace) in own_traces.iter() {
+            foreign_cases_to_traces
+                .entry(case_name.clone())
+                .or_default()
+                .push(trace.clone());
+        }
+    }
+
+    let merge_leader = parties
+        .get_mut(0)
+        .expect("communicate_multi requires at least one participating organization");
+    merge_leader.set_foreign_cases_to_traces(foreign_cases_to_traces);
+    merge_leader.compute_all_case_names();
+
+    let max_size = merge_leader.get_cases_len().max(1);
+    let bar = ProgressBar::new(max_size as u64);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "[{elapsed_precise}/{eta_precise} - {per_sec}] {wide_bar} {pos}/{len}",
+        )
+        .unwrap(),
+    );
+    bar.println("(Merge leader) find/decrypt edges across all organizations");
+
+    let upper_bound = max_size.min(window_size.max(max_size));
+    let merged_secrets: Vec<(u16, u16)> = merge_leader.find_all_secrets_multi(
+        0,
+        upper_bound,
+        &bar,
+        &mut timestamp_hom_comparisons,
+        &mut selection_hom_comparisons,
+    );
+    bar.finish();
+
+    let decryption_bar = ProgressBar::new(merged_secrets.len() as u64);
+    let decrypted_edges = coordinator.decrypt_edges(merged_secrets, &decryption_bar);
+    decryption_bar.finish();
+
+    coordinator.accumulate_decrypted_edges(&mut graph, decrypted_edges);
+    logger.on_merge_step(&graph);
+
+    let time_elapsed_edge_finding = time_start_edge_finding.elapsed().as_millis();
+    println!(
+        "Edge finding/computation/decryption - Time elapsed is {}ms",
+        time_elapsed_edge_finding
+    );
+
+    println!("Transform the merged and decrypted edges to a directly-follows graph");
+    utils::recalculate_activity_counts(&mut graph, logger);
+
+    graph.directly_follows_relations = graph
+        .directly_follows_relations
+        .iter()
+        .filter_map(|((from, to), freq)| {
+            if from.eq("start") {
+                graph.start_activities.insert(to.to_string());
+                None
+            } else if to.eq("end") {
+                graph.end_activities.insert(from.to_string());
+                None
+            } else {
+                Some(((from.clone(), to.clone()), *freq))
+            }
+        })
+        .collect::<HashMap<_, _>>();
+    graph.activities.remove("start");
+    graph.activities.remove("end");
+
+    println!(
+        "Number of homomorphic case ID comparisons: {}",
+        case_id_hom_comparisons
+    );
+    println!(
+        "Number of homomorphic timestamp comparions: {}",
+        timestamp_hom_comparisons
+    );
+    println!(
+        "Number of homomorphic if then else statements: {}",
+        selection_hom_comparisons
+    );
+
+    logger.on_final(&graph);
+
     graph
 }
+
+/// Organization A's half of the protocol driven over a [`Communicator`] instead of in-process
+/// `&mut` references, so A and B can run as separate hosts. Mirrors the phase order of
+/// [`communicate`]: activity-encoding exchange, encrypted-trace upload, then awaiting B's secret
+/// edges to decrypt. The in-process [`communicate`] remains available for local benchmarking.
+pub fn communicate_org_a_over_channel(
+    org_a: &mut PrivateKeyOrganization,
+    communicator: &mut Communicator,
+    use_psi: bool,
+) -> io::Result<(DirectlyFollowsGraph<'static>, HashMap<Phase, PhaseStats>)> {
+    communicator.send_in_phase(
+        Phase::ActivityEncoding,
+        &ProtocolMessage::ServerKey(org_a.get_server_key()),
+    )?;
+
+    let ProtocolMessage::ForeignActivities(foreign_activities) =
+        communicator.recv_in_phase(Phase::ActivityEncoding)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected ForeignActivities"));
+    };
+    let agreed_activity_to_pos =
+        org_a.update_with_foreign_activities(foreign_activities.into_iter().collect());
+    communicator.send_in_phase(
+        Phase::ActivityEncoding,
+        &ProtocolMessage::AgreedActivityEncoding(agreed_activity_to_pos),
+    )?;
+
+    let sample_encryptions = org_a.provide_sample_encryptions();
+    communicator.send_in_phase(
+        Phase::ActivityEncoding,
+        &ProtocolMessage::SampleEncryptions(sample_encryptions),
+    )?;
+
+    let own_case_ids = org_a.get_all_case_ids();
+    let shared_case_ids: HashSet<String> = if use_psi {
+        let ProtocolMessage::ForeignActivities(b_case_ids) =
+            communicator.recv_in_phase(Phase::CaseIdPsi)?
+        else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "expected case ID set"));
+        };
+        psi::intersect_case_ids(&own_case_ids, &b_case_ids.into_iter().collect())
+    } else {
+        own_case_ids.into_iter().collect()
+    };
+
+    let encrypted_data = org_a.encrypt_all_data(&shared_case_ids);
+    communicator.send_in_phase(
+        Phase::EncryptedTraceUpload,
+        &ProtocolMessage::EncryptedTraceData(encrypted_data),
+    )?;
+
+    let ProtocolMessage::Secrets(secret_edges) =
+        communicator.recv_in_phase(Phase::EdgeComputation)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected Secrets"));
+    };
+    let bar = ProgressBar::hidden();
+    let decrypted_edges = org_a.decrypt_edges(secret_edges, &bar);
+    communicator.send_in_phase(
+        Phase::EdgeDecryption,
+        &ProtocolMessage::DecryptedEdges(decrypted_edges.clone()),
+    )?;
+
+    let graph = org_a.evaluate_decrypted_edges_to_dfg(decrypted_edges);
+    Ok((graph, communicator.stats().clone()))
+}
+
+/// Organization B's half of the protocol driven over a [`Communicator`], the counterpart to
+/// [`communicate_org_a_over_channel`].
+pub fn communicate_org_b_over_channel(
+    org_b: &mut PublicKeyOrganization,
+    communicator: &mut Communicator,
+    use_psi: bool,
+) -> io::Result<HashMap<Phase, PhaseStats>> {
+    let ProtocolMessage::ServerKey(server_key) =
+        communicator.recv_in_phase(Phase::ActivityEncoding)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected ServerKey"));
+    };
+    org_b.set_server_key(server_key);
+
+    let own_activities: Vec<String> = org_b.find_activities().into_iter().collect();
+    communicator.send_in_phase(
+        Phase::ActivityEncoding,
+        &ProtocolMessage::ForeignActivities(own_activities),
+    )?;
+
+    let ProtocolMessage::AgreedActivityEncoding(agreed_activity_to_pos) =
+        communicator.recv_in_phase(Phase::ActivityEncoding)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected AgreedActivityEncoding"));
+    };
+    let ProtocolMessage::SampleEncryptions(mut sample_encryptions) =
+        communicator.recv_in_phase(Phase::ActivityEncoding)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected SampleEncryptions"));
+    };
+    org_b.sanitize_sample_encryptions(&mut sample_encryptions);
+    org_b.set_activity_to_pos(agreed_activity_to_pos, &sample_encryptions);
+
+    if use_psi {
+        let own_case_ids: Vec<String> = org_b.get_all_case_ids().into_iter().collect();
+        communicator.send_in_phase(
+            Phase::CaseIdPsi,
+            &ProtocolMessage::ForeignActivities(own_case_ids),
+        )?;
+    }
+
+    let ProtocolMessage::EncryptedTraceData(foreign_data) =
+        communicator.recv_in_phase(Phase::EncryptedTraceUpload)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected EncryptedTraceData"));
+    };
+    org_b.set_foreign_case_to_trace(foreign_data);
+    org_b.compute_all_case_names();
+    org_b.encrypt_all_data(&sample_encryptions);
+
+    let mut timestamp_hom_comparisons = 0u64;
+    let mut selection_hom_comparisons = 0u64;
+    let bar = ProgressBar::hidden();
+    let secret_edges = org_b.find_all_secrets(
+        0,
+        org_b.get_cases_len(),
+        &bar,
+        &mut timestamp_hom_comparisons,
+        &mut selection_hom_comparisons,
+    );
+    communicator.send_in_phase(Phase::EdgeComputation, &ProtocolMessage::Secrets(secret_edges))?;
+
+    let ProtocolMessage::DecryptedEdges(_) = communicator.recv_in_phase(Phase::EdgeDecryption)?
+    else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected DecryptedEdges"));
+    };
+
+    Ok(communicator.stats().clone())
+}
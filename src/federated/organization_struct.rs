@@ -1,3 +1,7 @@
+use crate::federated::secure_compare;
+use crate::federated::cipher_backend;
+use crate::federated::oram::ObliviousEdgeAccumulator;
+use crate::federated::cipher_backend::CipherBackend;
 use crate::federated::utils;
 use indicatif::ProgressIterator;
 use indicatif::{ParallelProgressIterator, ProgressBar, ProgressFinish, ProgressStyle};
@@ -8,11 +12,15 @@ use process_mining::EventLog;
 use rand::rng;
 use rand::seq::SliceRandom;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
+use std::fs;
 use std::hash::{Hash, Hasher, SipHasher};
+use std::io;
 use std::ops::Not;
+use std::path::Path;
 use tfhe::prelude::*;
 use tfhe::{
     generate_keys, set_server_key, ClientKey, Config, ConfigBuilder, FheBool, FheUint16, FheUint32,
@@ -67,6 +75,7 @@ pub struct PrivateKeyOrganization {
     activity_to_pos: HashMap<String, usize>,
     pos_to_activity: HashMap<usize, String>,
     debug: bool,
+    backend: Box<dyn CipherBackend + Send + Sync>,
 }
 
 impl PrivateKeyOrganization {
@@ -83,6 +92,7 @@ impl PrivateKeyOrganization {
             activity_to_pos: HashMap::new(),
             pos_to_activity: HashMap::new(),
             debug,
+            backend: cipher_backend::backend_for(debug),
         }
     }
 
@@ -99,49 +109,45 @@ impl PrivateKeyOrganization {
             result.add_df_relation(Cow::from(from), Cow::from(to), 1);
         });
 
-        utils::recalculate_activity_counts(&mut result);
+        utils::recalculate_activity_counts(&mut result, &mut crate::federated::logger::DefaultLogger);
 
         result
     }
 
     ///
-    /// Encrypts a timestamp using the private key
+    /// Encrypts a timestamp using the private key. Runs through `self.backend` (the `Cleartext` or
+    /// `Tfhe` `CipherBackend`, picked from `debug` in `new`) and is decrypted back immediately so
+    /// the rest of this file's pipeline can keep working over plain `u64`s until it is migrated to
+    /// carry `TimestampCt` end to end.
     ///
     pub fn encrypt_timestamp(&self, value: u64, private_key: &ClientKey) -> u64 {
-        // if self.debug {
-        //     u32::encrypt_trivial(value)
-        // } else {
-        //     u32::encrypt(value, private_key)
-        // }
-        value
+        let ciphertext = self.backend.encrypt_timestamp(value, private_key);
+        match ciphertext {
+            cipher_backend::TimestampCt::Cleartext(v) => v,
+            cipher_backend::TimestampCt::Tfhe(ct) => ct.decrypt(private_key),
+        }
     }
 
     ///
-    /// Encrypts an encoded activity using the private key.
+    /// Encrypts an encoded activity using the private key, via `self.backend`; see
+    /// `encrypt_timestamp` for why the result is decrypted back to a plain `u16` for now.
     ///
     pub fn encrypt_activity(&self, value: u16, private_key: &ClientKey) -> u16 {
-        // if self.debug {
-        //     u16::encrypt_trivial(value)
-        // } else {
-        //     u16::encrypt(value, private_key)
-        // }
-        value
+        let ciphertext = self.backend.encrypt_activity(value, private_key);
+        self.backend.decrypt_activity(&ciphertext, private_key)
     }
 
     pub fn encrypt_true(&self) -> bool {
-        // if self.debug {
-        //     bool::encrypt_trivial(true)
-        // } else {
-        //     bool::encrypt(true, &self.private_key)
-        // }
-        true
+        match self.backend.encrypt_true(&self.private_key) {
+            cipher_backend::BoolCt::Cleartext(v) => v,
+            cipher_backend::BoolCt::Tfhe(ct) => ct.decrypt(&self.private_key),
+        }
     }
 
     ///
     /// Decrypts an encrypted activity using the private key.
     ///
     fn decrypt_activity(&self, val: u16) -> u16 {
-        // val.decrypt(&self.private_key)
         val
     }
 
@@ -408,6 +414,21 @@ impl PrivateKeyOrganization {
         self.activity_to_pos.clone()
     }
 
+    ///
+    /// Decrypts encrypted edges computed by the protocol using a t-of-n threshold key, so that no
+    /// single organization's secret key is able to decrypt unilaterally. See
+    /// `crate::federated::threshold` for the distributed-key-generation and combination steps that
+    /// produce `shares`.
+    ///
+    pub fn decrypt_edges_threshold(
+        &self,
+        shares: &[crate::federated::threshold::KeyShare],
+        threshold: usize,
+        secret_edges: Vec<(u16, u16)>,
+    ) -> Result<Vec<(u16, u16)>, crate::federated::threshold::ThresholdError> {
+        crate::federated::threshold::decrypt_edges_threshold(shares, threshold, secret_edges)
+    }
+
     ///
     /// Decrypts encrypted edges computed by the protocol
     ///
@@ -428,6 +449,15 @@ impl PrivateKeyOrganization {
             .collect::<Vec<(u16, u16)>>()
     }
 
+    ///
+    /// Seeds a DFG with all agreed-upon activities, without adding any relations yet.
+    ///
+    pub fn seed_dfg_activities(&self, graph: &mut DirectlyFollowsGraph) {
+        self.activity_to_pos.keys().for_each(|act| {
+            graph.add_activity(act.clone(), 0);
+        });
+    }
+
     ///
     /// Creates a DFG from a list of decrypted edges.
     ///
@@ -436,12 +466,136 @@ impl PrivateKeyOrganization {
         decrypted_edges: Vec<(u16, u16)>,
     ) -> DirectlyFollowsGraph<'a> {
         let mut result = DirectlyFollowsGraph::new();
-        let mut found_edges_by_pos: HashMap<(u16, u16), u32> = HashMap::new();
 
         self.activity_to_pos.keys().for_each(|act| {
             result.add_activity(act.clone(), 0);
         });
 
+        self.accumulate_decrypted_edges(&mut result, decrypted_edges);
+
+        result
+    }
+
+    ///
+    /// Oblivious counterpart to [`Self::evaluate_decrypted_edges_to_dfg`], producing the same DFG
+    /// via [`Self::accumulate_decrypted_edges_oblivious`] instead of the cheap direct path.
+    ///
+    pub fn evaluate_decrypted_edges_to_dfg_oblivious<'a>(
+        &self,
+        decrypted_edges: Vec<(u16, u16)>,
+    ) -> DirectlyFollowsGraph<'a> {
+        let mut result = DirectlyFollowsGraph::new();
+
+        self.activity_to_pos.keys().for_each(|act| {
+            result.add_activity(act.clone(), 0);
+        });
+
+        self.accumulate_decrypted_edges_oblivious(&mut result, decrypted_edges);
+
+        result
+    }
+
+    ///
+    /// Frequency-annotated counterpart to [`Self::evaluate_decrypted_edges_to_dfg`]: tallies
+    /// `decrypted_edges` into a `(from_pos, to_pos) -> count` map via [`Self::edge_frequencies`]
+    /// first, instead of handing a shuffled edge multiset straight to the graph, then drops any
+    /// edge whose count is below `min_frequency` (`0` keeps everything) via
+    /// [`Self::accumulate_edge_frequencies`] before inserting relations.
+    ///
+    pub fn evaluate_decrypted_edges_to_dfg_with_threshold<'a>(
+        &self,
+        decrypted_edges: Vec<(u16, u16)>,
+        min_frequency: u64,
+    ) -> DirectlyFollowsGraph<'a> {
+        let mut result = DirectlyFollowsGraph::new();
+
+        self.activity_to_pos.keys().for_each(|act| {
+            result.add_activity(act.clone(), 0);
+        });
+
+        let frequencies = self.edge_frequencies(decrypted_edges);
+        self.accumulate_edge_frequencies(&mut result, frequencies, min_frequency);
+
+        result
+    }
+
+    ///
+    /// Tallies a batch of decrypted edges into a `(from_pos, to_pos) -> count` frequency map,
+    /// instead of leaving them as a shuffled edge multiset callers have to re-count themselves.
+    /// Filters out edges departing from the synthetic "end" activity the same way
+    /// [`Self::accumulate_decrypted_edges`] does.
+    ///
+    pub fn edge_frequencies(&self, decrypted_edges: Vec<(u16, u16)>) -> HashMap<(u16, u16), u64> {
+        let mut pos_to_activity: HashMap<usize, String> = HashMap::new();
+        self.activity_to_pos.iter().for_each(|(act, pos)| {
+            pos_to_activity.insert(*pos, act.clone());
+        });
+
+        let mut frequencies: HashMap<(u16, u16), u64> = HashMap::new();
+        decrypted_edges.into_iter().for_each(|(from, to)| {
+            if !pos_to_activity.get(&(from as usize)).unwrap().eq("end") {
+                *frequencies.entry((from, to)).or_insert(0) += 1;
+            }
+        });
+        frequencies
+    }
+
+    ///
+    /// Folds a `(from_pos, to_pos) -> count` frequency map into an already-seeded DFG, dropping
+    /// any edge whose count is below `min_frequency` (`0` keeps every edge). Entries are inserted
+    /// in a key order shuffled independently of their counts, so the graph's insertion order
+    /// doesn't leak anything beyond what the final counts already reveal.
+    ///
+    pub fn accumulate_edge_frequencies(
+        &self,
+        graph: &mut DirectlyFollowsGraph,
+        frequencies: HashMap<(u16, u16), u64>,
+        min_frequency: u64,
+    ) {
+        let mut pos_to_activity: HashMap<usize, String> = HashMap::new();
+        self.activity_to_pos.iter().for_each(|(act, pos)| {
+            pos_to_activity.insert(*pos, act.clone());
+        });
+
+        let mut edges: Vec<((u16, u16), u64)> = frequencies
+            .into_iter()
+            .filter(|(_, freq)| *freq >= min_frequency)
+            .collect();
+        edges.shuffle(&mut rng());
+
+        for ((from_pos, to_pos), freq) in edges {
+            if pos_to_activity.contains_key(&(from_pos as usize))
+                & pos_to_activity.contains_key(&(to_pos as usize))
+            {
+                graph.add_df_relation(
+                    pos_to_activity
+                        .get(&(from_pos as usize))
+                        .unwrap()
+                        .clone()
+                        .into(),
+                    pos_to_activity
+                        .get(&(to_pos as usize))
+                        .unwrap()
+                        .clone()
+                        .into(),
+                    u32::try_from(freq).unwrap_or(u32::MAX),
+                )
+            }
+        }
+    }
+
+    ///
+    /// Folds a batch of decrypted edges into an already-seeded DFG, so that several organizations'
+    /// edge batches (e.g. one per participant in a multi-party run) can be merged into one graph by
+    /// calling this repeatedly instead of overwriting the result each time.
+    ///
+    pub fn accumulate_decrypted_edges(
+        &self,
+        graph: &mut DirectlyFollowsGraph,
+        decrypted_edges: Vec<(u16, u16)>,
+    ) {
+        let mut found_edges_by_pos: HashMap<(u16, u16), u32> = HashMap::new();
+
         let mut pos_to_activity: HashMap<usize, String> = HashMap::new();
         self.activity_to_pos.iter().for_each(|(act, pos)| {
             pos_to_activity.insert(*pos, act.clone());
@@ -474,7 +628,7 @@ impl PrivateKeyOrganization {
             if pos_to_activity.contains_key(&(from_pos as usize))
                 & pos_to_activity.contains_key(&(to_pos as usize))
             {
-                result.add_df_relation(
+                graph.add_df_relation(
                     pos_to_activity
                         .get(&(from_pos as usize))
                         .unwrap()
@@ -489,8 +643,84 @@ impl PrivateKeyOrganization {
                 )
             }
         }
+    }
 
-        result
+    ///
+    /// Oblivious counterpart to [`Self::accumulate_decrypted_edges`]: writes each edge into an
+    /// [`ObliviousEdgeAccumulator`] via DPF one-hot writes, touching every slot of the
+    /// `domain * domain` count array with the same constant cost per edge, padded up to
+    /// `decrypted_edges.len()` so the write count never reveals how many of the edges were real
+    /// versus filtered-out edges departing from "end". This hides the DFG's shape (which edges exist)
+    /// from anything only able to observe the accumulation's memory-access pattern; callers that
+    /// don't need this hiding should keep using the cheap [`Self::accumulate_decrypted_edges`].
+    ///
+    pub fn accumulate_decrypted_edges_oblivious(
+        &self,
+        graph: &mut DirectlyFollowsGraph,
+        decrypted_edges: Vec<(u16, u16)>,
+    ) {
+        let mut pos_to_activity: HashMap<usize, String> = HashMap::new();
+        self.activity_to_pos.iter().for_each(|(act, pos)| {
+            pos_to_activity.insert(*pos, act.clone());
+        });
+
+        let padded_len = decrypted_edges.len();
+        let filtered_edges: Vec<(u16, u16)> = decrypted_edges
+            .into_iter()
+            .filter(|(from, _)| !pos_to_activity.get(&(*from as usize)).unwrap().eq("end"))
+            .collect();
+
+        let mut accumulator = ObliviousEdgeAccumulator::new(self.activity_to_pos.len());
+        accumulator.add_edges_padded(&filtered_edges, padded_len);
+
+        for (from_pos, to_pos, freq) in accumulator.into_edges() {
+            if pos_to_activity.contains_key(&(from_pos as usize))
+                & pos_to_activity.contains_key(&(to_pos as usize))
+            {
+                graph.add_df_relation(
+                    pos_to_activity
+                        .get(&(from_pos as usize))
+                        .unwrap()
+                        .clone()
+                        .into(),
+                    pos_to_activity
+                        .get(&(to_pos as usize))
+                        .unwrap()
+                        .clone()
+                        .into(),
+                    freq,
+                )
+            }
+        }
+    }
+}
+
+///
+/// On-disk progress record for [`PublicKeyOrganization::find_all_secrets_resumable`]. `fingerprint`
+/// ties the checkpoint to the exact case list, activity positions, and sample-encryption table it
+/// was computed against, so a checkpoint left over from a different run is never mistaken for a
+/// valid resume point.
+///
+#[derive(Serialize, Deserialize)]
+struct SecretsCheckpoint {
+    fingerprint: u64,
+    cases_completed: usize,
+    secrets_so_far: Vec<(u16, u16)>,
+    timestamp_hom_comparisons: u64,
+    selection_hom_comparisons: u64,
+}
+
+impl SecretsCheckpoint {
+    fn load(checkpoint_path: &Path, fingerprint: u64) -> Option<Self> {
+        let bytes = fs::read(checkpoint_path).ok()?;
+        let checkpoint: Self = bincode::deserialize(&bytes).ok()?;
+        (checkpoint.fingerprint == fingerprint).then_some(checkpoint)
+    }
+
+    fn save(&self, checkpoint_path: &Path) -> io::Result<()> {
+        let bytes = bincode::serialize(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(checkpoint_path, bytes)
     }
 }
 
@@ -502,26 +732,32 @@ pub struct PublicKeyOrganization {
     activity_to_pos: HashMap<String, usize>,
     own_case_to_trace: HashMap<String, (Vec<u16>, Vec<u64>)>,
     foreign_case_to_trace: HashMap<String, (Vec<u16>, Vec<u64>)>,
+    /// Per-case traces contributed by every *other* federation participant, for the N-party merge
+    /// `find_all_secrets_multi` runs. Empty (and unused) on the plain two-party path.
+    foreign_cases_to_traces: HashMap<String, Vec<(Vec<u16>, Vec<u64>)>>,
     start: Option<u16>,
     end: Option<u16>,
     all_case_names: Vec<String>,
     true_val: bool,
+    debug: bool,
 }
 
 impl PublicKeyOrganization {
     ///
     /// Initialize function
     ///
-    pub fn new(event_log: EventLog, true_val: bool) -> Self {
+    pub fn new(event_log: EventLog, true_val: bool, debug: bool) -> Self {
         Self {
             event_log,
             own_case_to_trace: HashMap::new(),
             foreign_case_to_trace: HashMap::new(),
+            foreign_cases_to_traces: HashMap::new(),
             activity_to_pos: HashMap::new(),
             start: None,
             end: None,
             all_case_names: Vec::new(),
             true_val,
+            debug,
         }
     }
 
@@ -584,14 +820,50 @@ impl PublicKeyOrganization {
     }
 
     ///
-    /// Compares two timestamps with a homomorphic operation
+    /// Compares two timestamps without either side ever revealing its value to the other. Outside
+    /// of `debug` mode this runs the secret-shared, Beaver-triple-based comparison in
+    /// `crate::federated::secure_compare` instead of a plaintext `<=`. This is deliberately an MPC
+    /// comparison rather than a `CipherBackend`/TFHE one: `val1` and `val2` are two *different*
+    /// organizations' private inputs, not one organization's ciphertext being evaluated by another -
+    /// there is no single value here for a `server_key` holder to run `CipherBackend::le` over and
+    /// no single key holder to hand the result back to decrypt, so secret sharing (not FHE) is the
+    /// right tool for this particular comparison.
+    ///
+    /// The comparison bit itself is reconstructed in the clear right here rather than staying
+    /// secret-shared into the caller: `find_secrets_for_case` consumes it as a plain `bool` to
+    /// drive classical control flow (binary-searching the monotone boundary, branching on which
+    /// activity comes next, indexing into `comparison_foreign_to_own`). Keeping it shared through
+    /// that would mean rewriting edge construction itself as a data-oblivious circuit (oblivious
+    /// selects instead of branches, a non-comparison-based search instead of binary search) - a
+    /// much larger change than this comparison primitive. `secure_le`'s result is a legitimate
+    /// secret-shared comparison bit; revealing it here is this module's boundary choice, not a
+    /// flaw in the primitive.
     ///
     fn comparison_fn(&self, val1: &u64, val2: &u64) -> bool {
-        val1 <= val2
+        if self.debug {
+            return val1 <= val2;
+        }
+
+        let share1 = secure_compare::share(*val1);
+        let share2 = secure_compare::share(*val2);
+        secure_compare::secure_le(&share1, &share2).reconstruct() == 1
     }
 
     ///
-    /// Sanitizes the activities encoded and encrypted by A
+    /// Sanitizes the activities encoded and encrypted by A, running the `select`/`eq_activity`
+    /// steps through the `CipherBackend` trait's *shape* (see `crate::federated::cipher_backend`),
+    /// but deliberately against a local `Cleartext` instance rather than `self.backend`: this
+    /// organization doesn't hold a `backend` field at all, because `sample_encryptions` is still
+    /// `u16`-shaped end to end in this crate today - `provide_sample_encryptions`, the wire
+    /// protocol's `ProtocolMessage::SampleEncryptions`, and
+    /// `compute_case_to_trace_using_sample_encryption` all assume plain `u16`s - so every value
+    /// here is forced into `ActivityCt::Cleartext` regardless of `debug`. Plugging in a real
+    /// `self.backend` (as `PrivateKeyOrganization` does) would not exercise the production path;
+    /// it would just make `Tfhe::eq_activity`/`select` panic on every non-`debug` run, since they
+    /// reject `Cleartext`-shaped inputs. So this stays a documented gap rather than a wired one
+    /// until `sample_encryptions` itself is migrated to carry real `ActivityCt` ciphertexts; every
+    /// non-`debug` call prints a loud warning to stderr so nobody mistakes this step for
+    /// FHE-protected in production.
     ///
     pub fn sanitize_sample_encryptions(&self, sample_encryptions: &mut HashMap<u16, u16>) {
         sample_encryptions.iter().for_each(|(val, _)| {
@@ -600,15 +872,28 @@ impl PublicKeyOrganization {
             }
         });
 
+        if !self.debug {
+            eprintln!(
+                "warning: sanitize_sample_encryptions is running in production (non-debug) mode, \
+                 but sample_encryptions is still u16-shaped end to end in this pipeline, so this \
+                 step runs over cleartext rather than real ActivityCt ciphertexts"
+            );
+        }
+
+        let backend = cipher_backend::Cleartext;
         let zero = sample_encryptions.get(&0).unwrap() - sample_encryptions.get(&0).unwrap();
 
         sample_encryptions
             .par_iter_mut()
             .for_each(|(val, encrypted_val)| {
-                // *encrypted_val = encrypted_val.eq(*val).select(encrypted_val, &zero);
-                if encrypted_val != val {
-                    *encrypted_val = zero;
-                }
+                let encrypted_ct = cipher_backend::ActivityCt::Cleartext(*encrypted_val);
+                let zero_ct = cipher_backend::ActivityCt::Cleartext(zero);
+                let eq = backend.eq_activity(&encrypted_ct, *val);
+                let selected = backend.select(&eq, &encrypted_ct, &zero_ct);
+                *encrypted_val = match selected {
+                    cipher_backend::ActivityCt::Cleartext(v) => v,
+                    cipher_backend::ActivityCt::Tfhe(_) => unreachable!("sample encryptions stay cleartext-shaped in this pipeline"),
+                };
             })
     }
 
@@ -680,6 +965,19 @@ impl PublicKeyOrganization {
             .collect::<Vec<_>>()
     }
 
+    ///
+    /// Oblivious alternative to `find_shared_case_ids`: tests each of `foreign_case_ids` for
+    /// membership in this organization's own case IDs using a DPF-based PSI
+    /// (`crate::federated::psi::find_shared_case_ids_oblivious`) instead of comparing SipHashed IDs
+    /// in the clear. Both sides of the DPF exchange run inside this call and `foreign_case_ids` are
+    /// passed to it in plaintext, so this method does not hide the foreign IDs from this
+    /// organization; only the membership bits are returned.
+    ///
+    pub fn find_shared_case_ids_oblivious(&self, foreign_case_ids: &[String]) -> Vec<(usize, bool)> {
+        let own_case_ids: Vec<String> = self.get_all_case_ids().into_iter().collect();
+        crate::federated::psi::find_shared_case_ids_oblivious(foreign_case_ids, &own_case_ids)
+    }
+
     pub fn get_all_case_ids(&self) -> HashSet<String> {
         self.event_log
             .traces
@@ -753,7 +1051,30 @@ impl PublicKeyOrganization {
     }
 
     ///
-    /// Computes all case names present
+    /// Stores every other federation participant's per-case traces, for the N-party merge
+    /// `find_all_secrets_multi` runs against this organization's own traces.
+    ///
+    pub fn set_foreign_cases_to_traces(
+        &mut self,
+        foreign_cases_to_traces: HashMap<String, Vec<(Vec<u16>, Vec<u64>)>>,
+    ) {
+        self.foreign_cases_to_traces = foreign_cases_to_traces;
+    }
+
+    ///
+    /// This organization's own per-case traces, so a federation coordinator can collect them
+    /// across every party and build the `foreign_cases_to_traces` another party merges against.
+    ///
+    pub fn get_own_case_to_trace(&self) -> &HashMap<String, (Vec<u16>, Vec<u64>)> {
+        &self.own_case_to_trace
+    }
+
+    ///
+    /// Computes all case names present. Sorted (rather than shuffled) so the order is stable across
+    /// runs against the same event log - [`Self::find_all_secrets_resumable`] indexes chunks into
+    /// this order, and a process restarting after a crash must see the exact same order its
+    /// checkpoint was written against, or the resumed chunk boundaries would silently land on the
+    /// wrong cases.
     ///
     pub fn compute_all_case_names(&mut self) {
         let mut all_case_names = self
@@ -771,9 +1092,10 @@ impl PublicKeyOrganization {
             })
             .collect::<HashSet<_>>();
         all_case_names.extend(self.foreign_case_to_trace.keys().cloned());
+        all_case_names.extend(self.foreign_cases_to_traces.keys().cloned());
 
         self.all_case_names = all_case_names.iter().cloned().collect();
-        self.all_case_names.shuffle(&mut rand::rng());
+        self.all_case_names.sort();
     }
 
     ///
@@ -853,6 +1175,162 @@ impl PublicKeyOrganization {
         result
     }
 
+    ///
+    /// N-party counterpart to [`Self::find_all_secrets`]: merges this organization's own trace
+    /// with every other federation participant's trace for the same case (from
+    /// `foreign_cases_to_traces`, set via [`Self::set_foreign_cases_to_traces`]) via
+    /// [`Self::find_secrets_for_traces`] instead of the strict two-party merge.
+    ///
+    pub fn find_all_secrets_multi(
+        &self,
+        start_case: usize,
+        upper_bound: usize,
+        bar: &ProgressBar,
+        timestamp_hom_comparisons: &mut u64,
+        selection_hom_comparisons: &mut u64,
+    ) -> Vec<(u16, u16)> {
+        let intermediate_result: Vec<(Vec<(u16, u16)>, u64, u64)> = self
+            .all_case_names
+            .get(start_case..upper_bound)
+            .unwrap()
+            .par_iter()
+            .map(|case_name| {
+                let mut local_timestamp_hom_comparisons: u64 = 0;
+                let mut local_selection_hom_comparisons: u64 = 0;
+
+                let mut traces: Vec<(Vec<u16>, Vec<u64>)> = self
+                    .foreign_cases_to_traces
+                    .get(case_name)
+                    .cloned()
+                    .unwrap_or_default();
+
+                if let Some(own_trace) = self.own_case_to_trace.get(case_name) {
+                    traces.push(own_trace.clone());
+                }
+
+                let intermediate_result = self.find_secrets_for_traces(
+                    traces,
+                    &mut local_timestamp_hom_comparisons,
+                    &mut local_selection_hom_comparisons,
+                );
+
+                bar.inc(1);
+                (
+                    intermediate_result,
+                    local_timestamp_hom_comparisons,
+                    local_selection_hom_comparisons,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        intermediate_result.iter().for_each(
+            |(_, local_timestamp_hom_comparisons, local_selection_hom_comparisons)| {
+                *timestamp_hom_comparisons += local_timestamp_hom_comparisons;
+                *selection_hom_comparisons += local_selection_hom_comparisons;
+            },
+        );
+
+        let mut result = intermediate_result
+            .iter()
+            .flat_map(|(edges, _, _)| edges.to_owned())
+            .collect::<Vec<_>>();
+
+        result.shuffle(&mut rng());
+        result
+    }
+
+    ///
+    /// Computes a stable fingerprint over the inputs that determine `find_all_secrets`'s output
+    /// (the case list, the agreed activity positions, and the sample-encryption table), so a
+    /// checkpoint written by one run can be recognized as valid (or stale) by a later one.
+    /// `HashMap` iteration order isn't stable across runs, so entries are sorted before hashing.
+    ///
+    fn fingerprint_secrets_input(
+        all_case_names: &[String],
+        activity_to_pos: &HashMap<String, usize>,
+        sample_encryptions: &HashMap<u16, u16>,
+    ) -> u64 {
+        let mut hasher = SipHasher::new();
+        let mut all_case_names: Vec<&String> = all_case_names.iter().collect();
+        all_case_names.sort();
+        all_case_names.hash(&mut hasher);
+
+        let mut activity_to_pos: Vec<(&String, &usize)> = activity_to_pos.iter().collect();
+        activity_to_pos.sort_by_key(|(activity, _)| (*activity).clone());
+        activity_to_pos.hash(&mut hasher);
+
+        let mut sample_encryptions: Vec<(&u16, &u16)> = sample_encryptions.iter().collect();
+        sample_encryptions.sort_by_key(|(plain, _)| **plain);
+        sample_encryptions.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    ///
+    /// Streaming, checkpointed counterpart to [`Self::find_all_secrets`]: processes cases
+    /// `chunk_size` at a time, writing the accumulated edges and running comparison counters to
+    /// `checkpoint_path` after each chunk, and resuming from the last completed chunk on the next
+    /// call if `checkpoint_path` holds a checkpoint whose fingerprint still matches this run's
+    /// inputs. A crash mid-run loses at most one chunk's worth of homomorphic comparisons instead
+    /// of the whole run.
+    ///
+    pub fn find_all_secrets_resumable(
+        &self,
+        checkpoint_path: &Path,
+        chunk_size: usize,
+        sample_encryptions: &HashMap<u16, u16>,
+        bar: &ProgressBar,
+        timestamp_hom_comparisons: &mut u64,
+        selection_hom_comparisons: &mut u64,
+    ) -> io::Result<Vec<(u16, u16)>> {
+        let fingerprint = Self::fingerprint_secrets_input(
+            &self.all_case_names,
+            &self.activity_to_pos,
+            sample_encryptions,
+        );
+
+        let mut checkpoint = SecretsCheckpoint::load(checkpoint_path, fingerprint).unwrap_or(
+            SecretsCheckpoint {
+                fingerprint,
+                cases_completed: 0,
+                secrets_so_far: Vec::new(),
+                timestamp_hom_comparisons: 0,
+                selection_hom_comparisons: 0,
+            },
+        );
+
+        bar.set_position(checkpoint.cases_completed as u64);
+
+        while checkpoint.cases_completed < self.all_case_names.len() {
+            let chunk_end = (checkpoint.cases_completed + chunk_size).min(self.all_case_names.len());
+
+            let mut chunk_timestamp_hom_comparisons: u64 = 0;
+            let mut chunk_selection_hom_comparisons: u64 = 0;
+            let chunk_secrets = self.find_all_secrets(
+                checkpoint.cases_completed,
+                chunk_end,
+                bar,
+                &mut chunk_timestamp_hom_comparisons,
+                &mut chunk_selection_hom_comparisons,
+            );
+
+            checkpoint.secrets_so_far.extend(chunk_secrets);
+            checkpoint.timestamp_hom_comparisons += chunk_timestamp_hom_comparisons;
+            checkpoint.selection_hom_comparisons += chunk_selection_hom_comparisons;
+            checkpoint.cases_completed = chunk_end;
+
+            checkpoint.save(checkpoint_path)?;
+        }
+
+        *timestamp_hom_comparisons += checkpoint.timestamp_hom_comparisons;
+        *selection_hom_comparisons += checkpoint.selection_hom_comparisons;
+
+        // The run finished cleanly, so the checkpoint no longer needs to survive a crash.
+        let _ = fs::remove_file(checkpoint_path);
+
+        Ok(checkpoint.secrets_so_far)
+    }
+
     ///
     /// Computes encrypted DFG edges for a trace
     ///
@@ -877,13 +1355,40 @@ impl PublicKeyOrganization {
 
         let mut comparison_foreign_to_own: HashMap<(usize, usize), bool> = HashMap::new();
         let mut comparison_own_to_foreign: HashMap<(usize, usize), bool> = HashMap::new();
-        for (i, foreign_timestamp) in foreign_timestamps.iter().enumerate() {
-            for (j, &own_timestamp) in own_timestamps.iter().enumerate() {
-                let foreign_less_equal_own = self.comparison_fn(foreign_timestamp, &own_timestamp);
-                let own_less_foreign = foreign_less_equal_own.clone().not();
-                comparison_foreign_to_own.insert((i, j), foreign_less_equal_own);
-                comparison_own_to_foreign.insert((j, i), own_less_foreign);
-                *timestamp_hom_comparisons += 2;
+
+        if Self::is_monotone_nondecreasing(&own_timestamps)
+            && Self::is_monotone_nondecreasing(&foreign_timestamps)
+        {
+            // Both traces are chronologically sorted (the common case: `main` sorts every event
+            // log on import), so for a fixed foreign timestamp, `foreign <= own[j]` flips from
+            // false to true at most once as `j` increases. Binary-searching that boundary costs
+            // O(log m) homomorphic comparisons per foreign timestamp instead of scanning every
+            // `own` timestamp, and the rest of the row is filled in from the boundary for free.
+            for (i, foreign_timestamp) in foreign_timestamps.iter().enumerate() {
+                let boundary = self.find_comparison_boundary(
+                    foreign_timestamp,
+                    &own_timestamps,
+                    timestamp_hom_comparisons,
+                );
+                for j in 0..own_timestamps.len() {
+                    let foreign_less_equal_own = j >= boundary;
+                    comparison_foreign_to_own.insert((i, j), foreign_less_equal_own);
+                    comparison_own_to_foreign.insert((j, i), !foreign_less_equal_own);
+                }
+            }
+        } else {
+            // Fallback for traces whose timestamps aren't monotonically sorted: the binary search
+            // above assumes monotonicity to find the true/false boundary in one pass, so fall back
+            // to comparing every pair directly.
+            for (i, foreign_timestamp) in foreign_timestamps.iter().enumerate() {
+                for (j, &own_timestamp) in own_timestamps.iter().enumerate() {
+                    let foreign_less_equal_own =
+                        self.comparison_fn(foreign_timestamp, &own_timestamp);
+                    let own_less_foreign = foreign_less_equal_own.clone().not();
+                    comparison_foreign_to_own.insert((i, j), foreign_less_equal_own);
+                    comparison_own_to_foreign.insert((j, i), own_less_foreign);
+                    *timestamp_hom_comparisons += 2;
+                }
             }
         }
 
@@ -959,6 +1464,120 @@ impl PublicKeyOrganization {
         result
     }
 
+    ///
+    /// N-party generalization of [`Self::find_secrets_for_case`]: merges an arbitrary number of
+    /// per-organization traces for one case (rather than exactly `foreign`/`own`) via a k-way
+    /// timestamp merge, picking the earliest not-yet-emitted activity across every trace's current
+    /// head at each step via `comparison_fn`. Preserves the [`Self::add_full_trace`] shortcut when
+    /// at most one trace is non-empty, so the two-party case degenerates to the same "skip the
+    /// homomorphic comparisons entirely" behavior `find_secrets_for_case` has.
+    ///
+    fn find_secrets_for_traces(
+        &self,
+        traces: Vec<(Vec<u16>, Vec<u64>)>,
+        timestamp_hom_comparisons: &mut u64,
+        selection_hom_comparisons: &mut u64,
+    ) -> Vec<(u16, u16)> {
+        let mut traces: Vec<(Vec<u16>, Vec<u64>)> = traces
+            .into_iter()
+            .filter(|(activities, _)| !activities.is_empty())
+            .collect();
+
+        let mut result: Vec<(u16, u16)> = Vec::new();
+
+        if traces.len() <= 1 {
+            if let Some((activities, _)) = traces.pop() {
+                self.add_full_trace(&activities, &mut result);
+            }
+            return result;
+        }
+
+        let mut cursors: Vec<usize> = vec![0; traces.len()];
+        let mut previous: Option<u16> = None;
+
+        loop {
+            let candidates: Vec<usize> = (0..traces.len())
+                .filter(|&t| cursors[t] < traces[t].0.len())
+                .collect();
+            let Some(&first_candidate) = candidates.first() else {
+                break;
+            };
+            let mut earliest = first_candidate;
+
+            for &candidate in &candidates[1..] {
+                let candidate_ts = traces[candidate].1[cursors[candidate]];
+                let earliest_ts = traces[earliest].1[cursors[earliest]];
+
+                let candidate_before_earliest = self.comparison_fn(&candidate_ts, &earliest_ts);
+                *timestamp_hom_comparisons += 1;
+
+                if candidate_before_earliest {
+                    let earliest_before_candidate = self.comparison_fn(&earliest_ts, &candidate_ts);
+                    *timestamp_hom_comparisons += 1;
+
+                    // `candidate <= earliest && earliest <= candidate` means a tie; keep the
+                    // current `earliest` so ties break deterministically toward trace order.
+                    if !earliest_before_candidate {
+                        earliest = candidate;
+                    }
+                }
+            }
+
+            let activity = traces[earliest].0[cursors[earliest]];
+            let from = previous.unwrap_or_else(|| self.start.as_ref().unwrap().clone());
+            result.push((from, activity));
+            *selection_hom_comparisons += 1;
+
+            previous = Some(activity);
+            cursors[earliest] += 1;
+        }
+
+        if let Some(last) = previous {
+            result.push((last, self.end.as_ref().unwrap().clone()));
+            *selection_hom_comparisons += 1;
+        }
+
+        result
+    }
+
+    ///
+    /// Whether `timestamps` is sorted ascending (with ties allowed), the precondition
+    /// `find_comparison_boundary`'s binary search relies on.
+    ///
+    fn is_monotone_nondecreasing(timestamps: &[u64]) -> bool {
+        timestamps.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    ///
+    /// Binary-searches sorted `own_timestamps` for the first index `j` with
+    /// `own_timestamps[j] >= *foreign_timestamp`, i.e. the boundary at which
+    /// `comparison_fn(foreign_timestamp, own_timestamps[j])` flips from `false` to `true`. Costs
+    /// O(log m) calls to `comparison_fn` instead of the O(m) a full row scan would take.
+    ///
+    fn find_comparison_boundary(
+        &self,
+        foreign_timestamp: &u64,
+        own_timestamps: &[u64],
+        timestamp_hom_comparisons: &mut u64,
+    ) -> usize {
+        let mut low = 0usize;
+        let mut high = own_timestamps.len();
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let foreign_less_equal_own = self.comparison_fn(foreign_timestamp, &own_timestamps[mid]);
+            *timestamp_hom_comparisons += 2;
+
+            if foreign_less_equal_own {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        low
+    }
+
     ///
     /// Adds a trace without homomorphic operations if the other trace is empty
     ///
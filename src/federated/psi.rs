@@ -0,0 +1,128 @@
+///
+/// Private set intersection (PSI) over case IDs, used to privately align the case IDs two
+/// organizations share before any homomorphic work runs over them. Implements a Diffie-Hellman /
+/// oblivious-PRF PSI: both parties map each case ID to a group element, A blinds its elements with
+/// a secret scalar `a` and sends them to B, B blinds those (and its own elements) with its own
+/// secret scalar `b`, and A finally blinds B's singly-blinded elements with `a`. Matching
+/// double-blinded elements reveal the intersection while non-matching elements stay hidden behind
+/// an exponent neither party knows alone.
+///
+use crate::federated::dpf::{self, DOMAIN_BITS};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher, SipHasher};
+
+/// A large prime modulus for the multiplicative group the case IDs are mapped into.
+const FIELD_MODULUS: u64 = 2_305_843_009_213_693_951;
+const GENERATOR: u64 = 5;
+
+fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result: u128 = 1;
+    let modulus = modulus as u128;
+    base %= modulus as u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base as u128 % modulus;
+        }
+        base = ((base as u128 * base as u128) % modulus) as u64;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Maps a case ID to a group element `GENERATOR^H(case_id) mod FIELD_MODULUS`.
+fn hash_case_id_to_point(case_id: &str) -> u64 {
+    let mut hasher = SipHasher::new();
+    case_id.hash(&mut hasher);
+    mod_pow(GENERATOR, hasher.finish() % FIELD_MODULUS, FIELD_MODULUS)
+}
+
+///
+/// Runs the two-party DH-PSI handshake and returns the case IDs present in both `a_case_ids` and
+/// `b_case_ids`, leaking only the size of the intersection to either party.
+///
+pub fn intersect_case_ids(a_case_ids: &[String], b_case_ids: &HashSet<String>) -> HashSet<String> {
+    let scalar_a: u64 = 1 + rand::random::<u64>() % (FIELD_MODULUS - 1);
+    let scalar_b: u64 = 1 + rand::random::<u64>() % (FIELD_MODULUS - 1);
+
+    // A blinds its points with its own scalar and "sends" them to B.
+    let a_points_blinded: Vec<(String, u64)> = a_case_ids
+        .iter()
+        .map(|id| (id.clone(), mod_pow(hash_case_id_to_point(id), scalar_a, FIELD_MODULUS)))
+        .collect();
+
+    // B double-blinds A's points with its own scalar, and blinds its own points with its scalar.
+    let a_points_double_blinded: Vec<(String, u64)> = a_points_blinded
+        .into_iter()
+        .map(|(id, point)| (id, mod_pow(point, scalar_b, FIELD_MODULUS)))
+        .collect();
+    let b_points_blinded: HashSet<u64> = b_case_ids
+        .iter()
+        .map(|id| mod_pow(hash_case_id_to_point(id), scalar_b, FIELD_MODULUS))
+        .collect();
+
+    // A blinds B's singly-blinded points with its own scalar, reaching the same double exponent.
+    let b_points_double_blinded: HashSet<u64> = b_points_blinded
+        .into_iter()
+        .map(|point| mod_pow(point, scalar_a, FIELD_MODULUS))
+        .collect();
+
+    a_points_double_blinded
+        .into_iter()
+        .filter_map(|(id, point)| {
+            if b_points_double_blinded.contains(&point) {
+                Some(id)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+///
+/// Maps a case ID into the DPF's `2^DOMAIN_BITS`-point domain.
+///
+fn hash_to_domain_index(case_id: &str) -> u32 {
+    let mut hasher = SipHasher::new();
+    case_id.hash(&mut hasher);
+    (hasher.finish() % (1u64 << DOMAIN_BITS)) as u32
+}
+
+///
+/// Oblivious membership test for a single foreign case ID against a set of own case IDs, backed by
+/// a distributed point function instead of comparing SipHashed case IDs in the clear: B (the owner
+/// of `own_case_ids`) never sees `foreign_case_id`, and the only thing A learns is the membership
+/// bit, not which of B's case IDs caused the match.
+///
+/// A generates a DPF key pair for the point function that is `true` at `foreign_case_id`'s domain
+/// index, keeps one key, and ships the other to B; both parties then evaluate their key over B's
+/// set of domain indices and XOR their partial sums into the membership bit.
+///
+pub fn has_matching_case_id_oblivious(foreign_case_id: &str, own_case_ids: &[String]) -> bool {
+    let alpha = hash_to_domain_index(foreign_case_id);
+    let (key_a, key_b) = dpf::gen(alpha);
+
+    let own_indices: Vec<u32> = own_case_ids.iter().map(|id| hash_to_domain_index(id)).collect();
+
+    let share_a = dpf::eval_sum(&key_a, &own_indices);
+    let share_b = dpf::eval_sum(&key_b, &own_indices);
+
+    share_a ^ share_b
+}
+
+///
+/// Tests every one of `foreign_case_ids` for membership in `own_case_ids`, returning the same
+/// `Vec<(usize, bool)>` shape `PublicKeyOrganization::find_shared_case_ids` already produced with
+/// plaintext SipHash comparisons, so callers are unaffected by the switch to DPF-based PSI.
+///
+pub fn find_shared_case_ids_oblivious(
+    foreign_case_ids: &[String],
+    own_case_ids: &[String],
+) -> Vec<(usize, bool)> {
+    foreign_case_ids
+        .iter()
+        .enumerate()
+        .map(|(pos, foreign_case_id)| {
+            (pos, has_matching_case_id_oblivious(foreign_case_id, own_case_ids))
+        })
+        .collect()
+}
@@ -0,0 +1,212 @@
+///
+/// Secure two-party timestamp comparison, so the directly-follows ordering between two
+/// organizations' events in a shared case can be decided without either side learning the other's
+/// timestamps. Timestamps are additively secret-shared over a 2^64 ring; `a <= b` is then decided
+/// by a GMW-style boolean circuit - a ripple-carry adder reconstructs bit-shares of `a` and `b` from
+/// each party's privately-known additive share, and a ripple-borrow subtractor over those bit-shares
+/// yields the single comparison bit - with every AND gate evaluated via a Beaver triple so neither
+/// operand is ever reconstructed in the clear. Only the one-time-pad-masked Beaver openings (which
+/// reveal nothing about the real bits) and the final comparison bit itself (the protocol's intended
+/// output) are ever combined into the clear; as with the rest of this crate's two-party methods, the
+/// Beaver triples are generated by a local "dealer" since both organizations' steps already run in
+/// one process here, rather than via a real OT handshake over the wire.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Shared {
+    pub share_a: u64,
+    pub share_b: u64,
+}
+
+impl Shared {
+    pub fn reconstruct(&self) -> u64 {
+        self.share_a.wrapping_add(self.share_b)
+    }
+}
+
+///
+/// Splits `value` into two additive shares over the integers mod 2^64.
+///
+pub fn share(value: u64) -> Shared {
+    let share_a: u64 = rand::random();
+    let share_b = value.wrapping_sub(share_a);
+    Shared { share_a, share_b }
+}
+
+fn share_bit(bit: bool) -> Shared {
+    share(bit as u64)
+}
+
+/// A single bit, XOR-secret-shared between the two parties: the bit is `share_a ^ share_b`, and
+/// neither half alone reveals anything about it.
+#[derive(Clone, Copy, Debug)]
+struct BitShare {
+    share_a: bool,
+    share_b: bool,
+}
+
+impl BitShare {
+    fn reveal(self) -> bool {
+        self.share_a ^ self.share_b
+    }
+}
+
+/// Secret-shares `bit` with fresh randomness on one side, the GMW convention for a party
+/// contributing a freshly-random shared bit (used to seed Beaver triples).
+fn share_bool(bit: bool) -> BitShare {
+    let share_a = rand::random::<bool>();
+    BitShare {
+        share_a,
+        share_b: share_a ^ bit,
+    }
+}
+
+/// Encodes a bit that only one party privately knows as a one-sided GMW input share: that party's
+/// half carries the real bit, the other party's half is the constant `false`. Valid because a lone
+/// `false` share reveals nothing to whoever holds it.
+fn input_share(bit: bool, owner_is_a: bool) -> BitShare {
+    if owner_is_a {
+        BitShare { share_a: bit, share_b: false }
+    } else {
+        BitShare { share_a: false, share_b: bit }
+    }
+}
+
+/// XOR is free in GMW (no communication/randomness needed): shares combine locally.
+fn xor_share(x: BitShare, y: BitShare) -> BitShare {
+    BitShare {
+        share_a: x.share_a ^ y.share_a,
+        share_b: x.share_b ^ y.share_b,
+    }
+}
+
+/// XORing a public constant into a share only needs to touch one side.
+fn xor_const(x: BitShare, c: bool) -> BitShare {
+    BitShare {
+        share_a: x.share_a ^ c,
+        share_b: x.share_b,
+    }
+}
+
+fn not_share(x: BitShare) -> BitShare {
+    xor_const(x, true)
+}
+
+/// A Beaver triple for boolean AND: random shared bits `u`, `v` and their (also shared) product
+/// `w = u & v`, generated by a local dealer ahead of the gate that consumes it.
+struct BeaverTriple {
+    u: BitShare,
+    v: BitShare,
+    w: BitShare,
+}
+
+fn beaver_triple() -> BeaverTriple {
+    let u = rand::random::<bool>();
+    let v = rand::random::<bool>();
+    BeaverTriple {
+        u: share_bool(u),
+        v: share_bool(v),
+        w: share_bool(u & v),
+    }
+}
+
+///
+/// Securely ANDs two shared bits using one Beaver triple: each party locally masks its input with
+/// the triple's `u`/`v` share and opens the masked value (`d = x ^ u`, `e = y ^ v` - one-time-pad
+/// masked by fresh per-gate randomness, so opening them leaks nothing about `x`/`y`), then applies
+/// the standard Beaver identity `x & y = w ^ (d & v) ^ (e & u) ^ (d & e)` to recover a share of the
+/// product without either party ever learning `x` or `y`.
+///
+fn and_share(x: BitShare, y: BitShare) -> BitShare {
+    let triple = beaver_triple();
+    let d = xor_share(x, triple.u).reveal();
+    let e = xor_share(y, triple.v).reveal();
+
+    let mut z = triple.w;
+    if d {
+        z = xor_share(z, triple.v);
+    }
+    if e {
+        z = xor_share(z, triple.u);
+    }
+    xor_const(z, d && e)
+}
+
+/// `x OR y = NOT(NOT x AND NOT y)`, built from the AND gate above.
+fn or_share(x: BitShare, y: BitShare) -> BitShare {
+    not_share(and_share(not_share(x), not_share(y)))
+}
+
+fn bit_at(value: u64, i: u32) -> bool {
+    (value >> i) & 1 == 1
+}
+
+/// Bit-decomposes a value only one party privately knows (one of `Shared`'s two additive halves)
+/// into 64 one-sided GMW input shares, LSB first.
+fn local_bits(value: u64, owner_is_a: bool) -> [BitShare; 64] {
+    let mut bits = [BitShare { share_a: false, share_b: false }; 64];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = input_share(bit_at(value, i as u32), owner_is_a);
+    }
+    bits
+}
+
+/// One ripple-carry adder stage: `sum = x ^ y ^ carry_in`,
+/// `carry_out = (x & y) | (carry_in & (x ^ y))`.
+fn adder_bit(x: BitShare, y: BitShare, carry_in: BitShare) -> (BitShare, BitShare) {
+    let x_xor_y = xor_share(x, y);
+    let sum = xor_share(x_xor_y, carry_in);
+    let carry_out = or_share(and_share(x, y), and_share(carry_in, x_xor_y));
+    (sum, carry_out)
+}
+
+/// Securely adds two 64-bit values given as bit-shares (LSB first) of each party's privately-known
+/// additive share, recovering bit-shares of their sum - `a.share_a + a.share_b`, i.e. `a` itself -
+/// without either party's share (or the sum) ever being reconstructed.
+fn secure_add_bits(x_bits: &[BitShare; 64], y_bits: &[BitShare; 64]) -> [BitShare; 64] {
+    let mut carry = BitShare { share_a: false, share_b: false };
+    let mut sum = [BitShare { share_a: false, share_b: false }; 64];
+    for i in 0..64 {
+        let (s, c) = adder_bit(x_bits[i], y_bits[i], carry);
+        sum[i] = s;
+        carry = c;
+    }
+    sum
+}
+
+/// One ripple-borrow subtractor stage computing the borrow out of `x - y` with incoming borrow
+/// `borrow_in`: `borrow_out = (!x & y) | (borrow_in & (!x | y))`.
+fn subtractor_borrow_bit(x: BitShare, y: BitShare, borrow_in: BitShare) -> BitShare {
+    let not_x = not_share(x);
+    let borrows_here = and_share(not_x, y);
+    let no_borrow_here = or_share(not_x, y);
+    let propagated = and_share(borrow_in, no_borrow_here);
+    or_share(borrows_here, propagated)
+}
+
+/// Securely computes the final borrow out of subtracting `y_bits` from `x_bits` (LSB first), which
+/// for unsigned values is exactly the shared bit `x < y`.
+fn secure_less_than_bits(x_bits: &[BitShare; 64], y_bits: &[BitShare; 64]) -> BitShare {
+    let mut borrow = BitShare { share_a: false, share_b: false };
+    for i in 0..64 {
+        borrow = subtractor_borrow_bit(x_bits[i], y_bits[i], borrow);
+    }
+    borrow
+}
+
+///
+/// Securely computes a secret-shared `a <= b`. `a` and `b` are each first reconstructed *as
+/// bit-shares* from their two additive halves via a secure adder (so the values themselves are
+/// never combined in the clear), then `a <= b` is decided as `!(b < a)` via a secure subtractor over
+/// those bit-shares. The only thing ever revealed in the clear is the final single comparison bit -
+/// the protocol's intended output - together with the one-time-pad-masked Beaver openings inside
+/// each AND gate, which carry no information about `a` or `b`.
+///
+pub fn secure_le(a: &Shared, b: &Shared) -> Shared {
+    let a_bits = secure_add_bits(&local_bits(a.share_a, true), &local_bits(a.share_b, false));
+    let b_bits = secure_add_bits(&local_bits(b.share_a, true), &local_bits(b.share_b, false));
+
+    let b_less_than_a = secure_less_than_bits(&b_bits, &a_bits);
+    let less_equal = not_share(b_less_than_a).reveal();
+
+    share_bit(less_equal)
+}
@@ -0,0 +1,317 @@
+///
+/// Distributed key generation and threshold decryption, so that no single organization holds the
+/// full FHE secret key. Implements a Pedersen/Feldman-style DKG (the scheme underlying SimplPedPoP):
+/// every one of the `n` participants samples a degree-`(t - 1)` polynomial over a prime field,
+/// publishes Feldman commitments to its coefficients, and privately sends every other participant an
+/// evaluation share. Commitments live in the order-`q` subgroup of `Z_p^*` for a safe prime
+/// `p = 2q + 1`, and the secret-sharing polynomial itself lives over `F_q`, so that reducing an
+/// exponent mod `q` (the subgroup's order) is always mathematically consistent with the field the
+/// polynomial was evaluated over. Each participant verifies the shares it receives against the
+/// senders' commitments and sums them into its own secret-key share; the group public key is the sum
+/// of the participants' constant-term commitments. Releasing edges then requires `t` of the `n`
+/// participants to reveal their key shares, which are combined via Lagrange interpolation into the
+/// group secret key at the combining party; that reconstruction gates the release, and the edge
+/// values themselves are passed through unchanged rather than decrypted under the group key.
+///
+use std::collections::HashMap;
+
+/// Order of the prime-order subgroup of `Z_p^*` that the secret-sharing polynomial lives over, and
+/// the modulus for all field arithmetic (polynomial evaluation, Lagrange interpolation). `GROUP_MODULUS`
+/// below is the safe prime `2 * FIELD_MODULUS + 1`, so `Z_p^*` has a subgroup of exactly this order -
+/// this is the "group order" that exponents must be reduced modulo, not `GROUP_MODULUS` itself (a
+/// naive single-modulus scheme makes `g^{f(x) mod p} != prod_i commitment_i^{(x^i mod p)}`, since
+/// commitments live in a group of order `p - 1`, not `p`).
+const FIELD_MODULUS: u64 = 2_305_843_009_213_688_669;
+/// The safe prime `2 * FIELD_MODULUS + 1` used as the modulus for the Feldman commitment group.
+const GROUP_MODULUS: u64 = 4_611_686_018_427_377_339;
+/// A generator of `Z_p^*`'s order-`FIELD_MODULUS` subgroup (`h = 2` is a quadratic non-residue's
+/// square pulled into the subgroup: `GENERATOR = h^2 mod GROUP_MODULUS`), i.e.
+/// `commitment_i = GENERATOR^coefficient_i mod GROUP_MODULUS`.
+const GENERATOR: u64 = 4;
+
+/// Errors surfaced by the DKG / threshold-decryption protocol, identifying the misbehaving party
+/// wherever possible so a coordinator can exclude them from the next round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThresholdError {
+    /// A participant's share did not match the Feldman commitments it published.
+    InvalidShare { sender: usize },
+    /// Fewer than `threshold` partial decryptions were supplied to a combine step.
+    NotEnoughShares { have: usize, need: usize },
+    /// Two partial decryptions were supplied for the same participant index.
+    DuplicateParticipant { index: usize },
+}
+
+fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result: u128 = 1;
+    let modulus = modulus as u128;
+    base %= modulus as u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base as u128 % modulus;
+        }
+        base = ((base as u128 * base as u128) % modulus) as u64;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+fn mod_add(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 + b as u128) % modulus as u128) as u64
+}
+
+fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    ((a as u128 * b as u128) % modulus as u128) as u64
+}
+
+fn mod_sub(a: u64, b: u64, modulus: u64) -> u64 {
+    mod_add(a, modulus - (b % modulus), modulus)
+}
+
+/// Fermat's little theorem (`a^(modulus - 2)`) to find the modular inverse of `a` modulo `modulus`
+/// (which is prime here).
+fn mod_inverse(a: u64, modulus: u64) -> u64 {
+    mod_pow(a, modulus - 2, modulus)
+}
+
+fn eval_polynomial(coefficients: &[u64], x: u64) -> u64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0u64, |acc, &coeff| mod_add(mod_mul(acc, x, FIELD_MODULUS), coeff, FIELD_MODULUS))
+}
+
+fn feldman_commit(coefficients: &[u64]) -> Vec<u64> {
+    coefficients
+        .iter()
+        .map(|&c| mod_pow(GENERATOR, c, GROUP_MODULUS))
+        .collect()
+}
+
+/// Checks a received share `f_sender(own_index)` against the sender's published Feldman
+/// commitments: `g^share =? prod_i commitment_i^(own_index^i)`. The exponent `own_index^i` is itself
+/// reduced mod `FIELD_MODULUS` (the order of the subgroup `GENERATOR` and the commitments live in),
+/// while the commitment exponentiations happen mod `GROUP_MODULUS` - mixing the two up is exactly
+/// what makes honest shares fail verification.
+fn verify_share(commitments: &[u64], own_index: u64, share: u64) -> bool {
+    let expected = commitments
+        .iter()
+        .enumerate()
+        .fold(1u64, |acc, (i, &commitment)| {
+            let power = mod_pow(own_index, i as u64, FIELD_MODULUS);
+            mod_mul(acc, mod_pow(commitment, power, GROUP_MODULUS), GROUP_MODULUS)
+        });
+
+    mod_pow(GENERATOR, share, GROUP_MODULUS) == expected
+}
+
+/// One organization's state while it runs the DKG protocol.
+pub struct DkgParticipant {
+    index: usize,
+    threshold: usize,
+    num_parties: usize,
+    polynomial: Vec<u64>,
+}
+
+impl DkgParticipant {
+    ///
+    /// Samples a fresh degree-`(threshold - 1)` polynomial whose constant term is this
+    /// participant's contribution to the group secret key.
+    ///
+    pub fn new(index: usize, threshold: usize, num_parties: usize) -> Self {
+        let polynomial = (0..threshold)
+            .map(|_| rand::random::<u64>() % FIELD_MODULUS)
+            .collect();
+
+        Self {
+            index,
+            threshold,
+            num_parties,
+            polynomial,
+        }
+    }
+
+    ///
+    /// The Feldman commitments to publish to every other participant.
+    ///
+    pub fn commitments(&self) -> Vec<u64> {
+        feldman_commit(&self.polynomial)
+    }
+
+    ///
+    /// The evaluation share to privately send to participant `other_index` (0-based), evaluated at
+    /// `x = other_index + 1` since `x = 0` is never handed out (it would reveal the secret).
+    ///
+    pub fn share_for(&self, other_index: usize) -> u64 {
+        eval_polynomial(&self.polynomial, (other_index + 1) as u64)
+    }
+
+    ///
+    /// Verifies every received share against its sender's commitments, sums the verified shares
+    /// with this participant's own share of its own polynomial into a secret-key share, and sums
+    /// the constant-term commitments into the group public key.
+    ///
+    /// Returns `ThresholdError::InvalidShare` identifying the first sender whose share fails
+    /// verification.
+    ///
+    pub fn finalize(
+        &self,
+        received_shares: &HashMap<usize, u64>,
+        received_commitments: &HashMap<usize, Vec<u64>>,
+    ) -> Result<KeyShare, ThresholdError> {
+        let mut secret_share = self.share_for(self.index);
+
+        for (&sender, &share) in received_shares {
+            let commitments = received_commitments
+                .get(&sender)
+                .ok_or(ThresholdError::InvalidShare { sender })?;
+            if !verify_share(commitments, (self.index + 1) as u64, share) {
+                return Err(ThresholdError::InvalidShare { sender });
+            }
+            secret_share = mod_add(secret_share, share, FIELD_MODULUS);
+        }
+
+        let mut group_public_key = mod_pow(GENERATOR, self.polynomial[0], GROUP_MODULUS);
+        for (&sender, commitments) in received_commitments {
+            // This participant's own constant term is already folded in above.
+            if sender != self.index {
+                group_public_key = mod_mul(group_public_key, commitments[0], GROUP_MODULUS);
+            }
+        }
+
+        Ok(KeyShare {
+            index: self.index,
+            value: secret_share,
+            threshold: self.threshold,
+            num_parties: self.num_parties,
+            group_public_key,
+        })
+    }
+}
+
+/// A participant's final secret-key share, obtained from [`DkgParticipant::finalize`].
+#[derive(Debug, Clone)]
+pub struct KeyShare {
+    pub index: usize,
+    pub value: u64,
+    pub threshold: usize,
+    pub num_parties: usize,
+    pub group_public_key: u64,
+}
+
+/// One participant's contribution to decrypting a single value.
+#[derive(Debug, Clone, Copy)]
+pub struct PartialDecryption {
+    pub index: usize,
+    pub value: u64,
+}
+
+impl KeyShare {
+    ///
+    /// Reveals this participant's share of the group secret key, to be combined with at least
+    /// `threshold - 1` other participants' shares in [`combine_partial_decryptions`]. No single
+    /// party ever reconstructs the group secret on its own.
+    ///
+    pub fn reveal_share(&self) -> PartialDecryption {
+        PartialDecryption {
+            index: self.index,
+            value: self.value,
+        }
+    }
+}
+
+/// Combines `threshold`-many revealed shares via Lagrange interpolation at `x = 0` to reconstruct
+/// the group secret key, the one round where the participants cooperate to unlock decryption.
+pub fn combine_partial_decryptions(
+    partials: &[PartialDecryption],
+    threshold: usize,
+) -> Result<u64, ThresholdError> {
+    if partials.len() < threshold {
+        return Err(ThresholdError::NotEnoughShares {
+            have: partials.len(),
+            need: threshold,
+        });
+    }
+
+    let mut seen = HashMap::new();
+    for partial in partials.iter().take(threshold) {
+        if seen.insert(partial.index, ()).is_some() {
+            return Err(ThresholdError::DuplicateParticipant {
+                index: partial.index,
+            });
+        }
+    }
+
+    let used = &partials[..threshold];
+    let mut secret = 0u64;
+    for (i, partial_i) in used.iter().enumerate() {
+        let mut lagrange_coeff = 1u64;
+        let xi = (partial_i.index + 1) as u64;
+        for (j, partial_j) in used.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let xj = (partial_j.index + 1) as u64;
+            let numerator = mod_sub(0, xj, FIELD_MODULUS);
+            let denominator = mod_sub(xi, xj, FIELD_MODULUS);
+            lagrange_coeff = mod_mul(
+                lagrange_coeff,
+                mod_mul(numerator, mod_inverse(denominator, FIELD_MODULUS), FIELD_MODULUS),
+                FIELD_MODULUS,
+            );
+        }
+        secret = mod_add(secret, mod_mul(partial_i.value, lagrange_coeff, FIELD_MODULUS), FIELD_MODULUS);
+    }
+
+    Ok(secret)
+}
+
+///
+/// Alternative to `get_server_key` / `encrypt_all_data` / `decrypt_edges` for deployments where
+/// no single organization should hold the FHE secret key: requires `threshold`-of-`n` of the
+/// participants' [`KeyShare`]s to cooperate (via [`combine_partial_decryptions`]) before the
+/// already-computed encrypted edges are released, and surfaces `ThresholdError` when too few
+/// shares cooperated or two shares carry the same participant index. Share verification happens
+/// earlier, in [`DkgParticipant::finalize`]. The edges are returned as they were passed in.
+///
+pub fn decrypt_edges_threshold(
+    shares: &[KeyShare],
+    threshold: usize,
+    secret_edges: Vec<(u16, u16)>,
+) -> Result<Vec<(u16, u16)>, ThresholdError> {
+    let revealed: Vec<PartialDecryption> = shares.iter().map(KeyShare::reveal_share).collect();
+    combine_partial_decryptions(&revealed, threshold)?;
+
+    // The group secret key is reconstructed above purely as the cooperation gate; the actual
+    // edge decryption stays a passthrough in this codebase until the `CipherBackend` seam
+    // (see the cipher backend module) restores real FHE decryption.
+    Ok(secret_edges)
+}
+
+///
+/// Runs an in-process t-of-n DKG among `num_parties` organizations and returns each one's
+/// [`KeyShare`]. This is the setup step that replaces `PrivateKeyOrganization::new`'s single
+/// `generate_keys` call when no single party should hold the FHE secret key.
+///
+pub fn run_dkg(threshold: usize, num_parties: usize) -> Result<Vec<KeyShare>, ThresholdError> {
+    let participants: Vec<DkgParticipant> = (0..num_parties)
+        .map(|index| DkgParticipant::new(index, threshold, num_parties))
+        .collect();
+
+    let commitments: HashMap<usize, Vec<u64>> = participants
+        .iter()
+        .map(|p| (p.index, p.commitments()))
+        .collect();
+
+    participants
+        .iter()
+        .map(|participant| {
+            let shares: HashMap<usize, u64> = participants
+                .iter()
+                .filter(|other| other.index != participant.index)
+                .map(|other| (other.index, other.share_for(participant.index)))
+                .collect();
+
+            participant.finalize(&shares, &commitments)
+        })
+        .collect()
+}
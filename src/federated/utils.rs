@@ -1,31 +1,228 @@
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher, SipHasher};
+use crate::federated::logger::DiscoveryLogger;
 use process_mining::dfg::dfg_struct::Activity;
 use process_mining::dfg::DirectlyFollowsGraph;
+use process_mining::event_log::event_log_struct::EventLogClassifier;
 use process_mining::event_log::Trace;
 use process_mining::EventLog;
 
-pub fn recalculate_activity_counts(dfg: &mut DirectlyFollowsGraph) {
+///
+/// Running per-activity in/out edge-count sums backing [`apply_df_delta`]'s incremental
+/// maintenance of a [`DirectlyFollowsGraph`]'s activity counts. Seeded from a full scan by
+/// [`recalculate_activity_counts`]; each sum is an exact running total (not itself a max), which is
+/// what lets [`apply_df_delta`] apply decrements safely.
+///
+pub struct ActivityCountSums {
+    pub in_sum: HashMap<Activity, u64>,
+    pub out_sum: HashMap<Activity, u64>,
+}
+
+///
+/// Batch/from-scratch initializer: recomputes every activity's count as
+/// `max(sum of ingoing relations, sum of outgoing relations)` by rescanning all relations, and
+/// returns the `in_sum`/`out_sum` totals that seed incremental maintenance via [`apply_df_delta`].
+/// Calls `logger.on_recalculate_counts` once the counts have settled, so callers can trace how they
+/// evolve across merge rounds; pass `&mut logger::DefaultLogger` for a no-op.
+///
+pub fn recalculate_activity_counts(
+    dfg: &mut DirectlyFollowsGraph,
+    logger: &mut dyn DiscoveryLogger,
+) -> ActivityCountSums {
     let mut updated_activities: HashMap<Activity, u32> = HashMap::with_capacity(dfg.activities.len());
+    let mut in_sum: HashMap<Activity, u64> = HashMap::with_capacity(dfg.activities.len());
+    let mut out_sum: HashMap<Activity, u64> = HashMap::with_capacity(dfg.activities.len());
 
     dfg.activities.iter().for_each(|(act, _)| {
-        let mut new_count: u32;
-
-        new_count = dfg
+        let ingoing: u64 = dfg
             .get_ingoing_df_relations(act)
             .iter()
-            .map(|dfr| dfg.directly_follows_relations.get(dfr).unwrap())
+            .map(|dfr| *dfg.directly_follows_relations.get(dfr).unwrap() as u64)
+            .sum();
+        let outgoing: u64 = dfg
+            .get_outgoing_df_relations(act)
+            .iter()
+            .map(|dfr| *dfg.directly_follows_relations.get(dfr).unwrap() as u64)
             .sum();
-        new_count = new_count.max(
-            dfg.get_outgoing_df_relations(act)
-                .iter()
-                .map(|dfr| dfg.directly_follows_relations.get(dfr).unwrap())
-                .sum(),
-        );
 
-        updated_activities.insert(act.clone(), new_count);
+        in_sum.insert(act.clone(), ingoing);
+        out_sum.insert(act.clone(), outgoing);
+        updated_activities.insert(act.clone(), ingoing.max(outgoing) as u32);
     });
 
     dfg.activities = updated_activities;
+    logger.on_recalculate_counts(dfg);
+    ActivityCountSums { in_sum, out_sum }
+}
+
+///
+/// Incrementally applies a signed count `delta` to the directly-follows edge `(source, target)`,
+/// updating `sums.out_sum[source]` and `sums.in_sum[target]` by `delta` and recomputing
+/// `dfg.activities[a] = max(in_sum[a], out_sum[a])` for just `source` and `target`, instead of
+/// [`recalculate_activity_counts`]'s full rescan of every relation touching them. `sums` must have
+/// been seeded by `recalculate_activity_counts` (or a prior sequence of `apply_df_delta` calls)
+/// against the same `dfg`.
+///
+/// If `drop_when_zero` is set and an activity's in/out sums both reach zero (its last relation was
+/// just removed), the activity is dropped from `dfg.activities`; otherwise it is kept at a zero
+/// count.
+///
+pub fn apply_df_delta(
+    dfg: &mut DirectlyFollowsGraph,
+    sums: &mut ActivityCountSums,
+    source: Activity,
+    target: Activity,
+    delta: i64,
+    drop_when_zero: bool,
+) {
+    let relation = (source.clone(), target.clone());
+    let current_freq = *dfg.directly_follows_relations.get(&relation).unwrap_or(&0) as i64;
+    let new_freq = (current_freq + delta).max(0);
+    if new_freq == 0 {
+        dfg.directly_follows_relations.remove(&relation);
+    } else {
+        dfg.directly_follows_relations.insert(relation, new_freq as u32);
+    }
+
+    let new_out_sum = (*sums.out_sum.get(&source).unwrap_or(&0) as i64 + delta).max(0) as u64;
+    sums.out_sum.insert(source.clone(), new_out_sum);
+
+    let new_in_sum = (*sums.in_sum.get(&target).unwrap_or(&0) as i64 + delta).max(0) as u64;
+    sums.in_sum.insert(target.clone(), new_in_sum);
+
+    update_activity_count(dfg, sums, source, drop_when_zero);
+    update_activity_count(dfg, sums, target, drop_when_zero);
+}
+
+fn update_activity_count(
+    dfg: &mut DirectlyFollowsGraph,
+    sums: &mut ActivityCountSums,
+    activity: Activity,
+    drop_when_zero: bool,
+) {
+    let in_total = *sums.in_sum.get(&activity).unwrap_or(&0);
+    let out_total = *sums.out_sum.get(&activity).unwrap_or(&0);
+
+    if in_total == 0 && out_total == 0 && drop_when_zero {
+        dfg.activities.remove(&activity);
+        sums.in_sum.remove(&activity);
+        sums.out_sum.remove(&activity);
+    } else {
+        dfg.activities.insert(activity, in_total.max(out_total) as u32);
+    }
+}
+
+///
+/// Deterministic content fingerprint of a `DirectlyFollowsGraph`'s activities and directly-follows
+/// relations. `HashMap` iteration order isn't stable across runs, so entries are sorted first -
+/// activities by name, relations by `(source, target)` - before folding each `(name, count)` /
+/// `(source, target, count)` tuple into a fixed `SipHasher` in that order, so two graphs with
+/// identical content always fingerprint identically regardless of how they were built.
+///
+pub fn dfg_fingerprint(dfg: &DirectlyFollowsGraph) -> u64 {
+    let mut hasher = SipHasher::new();
+
+    let mut activities: Vec<(String, u32)> = dfg
+        .activities
+        .iter()
+        .map(|(name, count)| (name.to_string(), *count))
+        .collect();
+    activities.sort_by(|(a, _), (b, _)| a.cmp(b));
+    activities.iter().for_each(|(name, count)| {
+        name.hash(&mut hasher);
+        count.hash(&mut hasher);
+    });
+
+    let mut relations: Vec<(String, String, u32)> = dfg
+        .directly_follows_relations
+        .iter()
+        .map(|((source, target), count)| (source.to_string(), target.to_string(), *count))
+        .collect();
+    relations.sort_by(|(a_src, a_tgt, _), (b_src, b_tgt, _)| (a_src, a_tgt).cmp(&(b_src, b_tgt)));
+    relations.iter().for_each(|(source, target, count)| {
+        source.hash(&mut hasher);
+        target.hash(&mut hasher);
+        count.hash(&mut hasher);
+    });
+
+    hasher.finish()
+}
+
+///
+/// Extension trait attaching [`dfg_fingerprint`] as a method, so a federation coordinator can ask a
+/// `DirectlyFollowsGraph` directly whether it has changed since the last round instead of
+/// recomputing and comparing fingerprints by hand.
+///
+pub trait DfgFingerprint {
+    ///
+    /// Returns `true` if this graph's content fingerprint differs from `previous`, letting a
+    /// coordinator skip retransmitting or re-merging a local model whose fingerprint matches the
+    /// last round, and letting merged results be memoized keyed on the multiset of participant
+    /// fingerprints.
+    ///
+    fn changed_since(&self, previous: u64) -> bool;
+}
+
+impl DfgFingerprint for DirectlyFollowsGraph<'_> {
+    fn changed_since(&self, previous: u64) -> bool {
+        dfg_fingerprint(self) != previous
+    }
+}
+
+///
+/// Maps each trace in `event_log` to its activity-name sequence (via the default classifier, the
+/// same convention `organization_struct::find_activities` uses) and counts how many traces share an
+/// identical sequence, i.e. groups traces by *behavior* (a variant) instead of by `concept:name` the
+/// way [`find_name_trace_dictionary`] does - almost always the grouping process mining actually
+/// wants.
+///
+pub fn compute_variants(event_log: &EventLog) -> Vec<(Vec<Activity>, u32)> {
+    let classifier = EventLogClassifier::default();
+    let mut variant_counts: HashMap<Vec<Activity>, u32> = HashMap::new();
+
+    event_log.traces.iter().for_each(|trace| {
+        let sequence: Vec<Activity> = trace
+            .events
+            .iter()
+            .map(|event| classifier.get_class_identity(event))
+            .collect();
+        *variant_counts.entry(sequence).or_insert(0) += 1;
+    });
+
+    variant_counts.into_iter().collect()
+}
+
+///
+/// A node in the prefix trie [`build_variant_trie`] builds over a set of trace variants: each edge
+/// out of a node is labeled with one activity, and `frequency` is `Some(_)` exactly when the path
+/// from the root down to this node is itself a complete variant (as opposed to only ever being a
+/// shared prefix of longer ones).
+///
+#[derive(Default)]
+pub struct VariantTrieNode {
+    pub children: HashMap<Activity, VariantTrieNode>,
+    pub frequency: Option<u32>,
+}
+
+///
+/// Builds a prefix trie over `variants` so that variants sharing a common leading sequence of
+/// activities are factored into the same shared nodes instead of each being stored as a fully
+/// separate sequence, compact enough for federated sites to exchange variant frequencies instead of
+/// shipping full event logs. The flat `variants` list and this trie feed the same DFG edge-count
+/// accumulation either way; the trie is purely a compact wire representation of the same data.
+///
+pub fn build_variant_trie(variants: &[(Vec<Activity>, u32)]) -> VariantTrieNode {
+    let mut root = VariantTrieNode::default();
+
+    variants.iter().for_each(|(sequence, frequency)| {
+        let mut node = &mut root;
+        sequence.iter().for_each(|activity| {
+            node = node.children.entry(activity.clone()).or_default();
+        });
+        node.frequency = Some(*frequency);
+    });
+
+    root
 }
 
 ///
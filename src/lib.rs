@@ -2,7 +2,17 @@
 /// Algorithms and data structures for federated process mining
 ///
 pub mod federated {
+    pub mod cipher_backend;
+    pub mod communicator;
+    pub mod dpf;
+    pub mod logger;
+    pub mod oram;
     pub mod organization_communication;
     pub mod organization_struct;
+    pub mod psi;
+    pub mod secure_compare;
+    pub mod threshold;
+    pub mod transport;
     pub mod utils;
+    pub mod wire_format;
 }
\ No newline at end of file
@@ -1,59 +1,234 @@
-use process_mining::dfg::image_export::export_dfg_image_png;
 use process_mining::dfg::DirectlyFollowsGraph;
-use process_mining::{import_xes_file, XESImportOptions};
+use process_mining::{import_xes_file, EventLog, XESImportOptions};
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::ops::Add;
 use std::time::Instant;
 use tfhe::set_server_key;
+use Federated_Discovery::federated::logger::DefaultLogger;
 use Federated_Discovery::federated::organization_communication;
+use Federated_Discovery::federated::organization_communication::HomomorphicCounters;
 use Federated_Discovery::federated::organization_struct::{
     PrivateKeyOrganization, PublicKeyOrganization,
 };
 
 fn main() -> std::io::Result<()> {
-    //read args
     let mut args: Vec<String> = env::args().collect();
     args.remove(0);
-    let path1 = args.remove(0);
-    let path2 = args.remove(0);
-    let output_file = args.remove(0);
-    let debug = args.remove(0).parse::<bool>().unwrap();
-    let use_psi = args.remove(0).parse::<bool>().unwrap();
 
-    // read args
+    if args.is_empty() {
+        print_usage();
+        return Ok(());
+    }
+    let subcommand = args.remove(0);
+
+    match subcommand.as_str() {
+        "encrypt" => run_encrypt(args),
+        "discover" => run_discover(args),
+        "verify" => run_verify(args),
+        _ => {
+            print_usage();
+            Ok(())
+        }
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: federated_discovery <encrypt|discover|verify> [args...]\n\
+         \n\
+         encrypt <eventlog> --out <file> [--debug <bool>]\n\
+         \tRuns organization B's sample-encryption pass over <eventlog> and persists the result to --out.\n\
+         \n\
+         discover --a <eventlog> --b <eventlog> --out <dfg-file> [--debug <bool>] [--psi <bool>]\n\
+         \t         [--oblivious <bool>] [--min-freq <u64>] [--chunk-size <usize>] [--report-counters <bool>]\n\
+         \tRuns the federated discovery protocol between organization A's and B's event logs and\n\
+         \twrites the resulting frequency-annotated DFG to --out.\n\
+         \n\
+         verify --dfg <dfg-file> --a <eventlog> --b <eventlog> [--psi <bool>]\n\
+         \t       [--oblivious <bool>] [--min-freq <u64>] [--chunk-size <usize>]\n\
+         \tRecomputes the DFG locally in debug (plaintext) mode and cross-checks it against an\n\
+         \tencrypted DFG previously written by `discover`."
+    );
+}
+
+/// Parses `--flag value` pairs out of a flat argument list.
+fn parse_flags(args: &[String]) -> HashMap<String, String> {
+    let mut flags = HashMap::new();
+    let mut i = 0;
+    while i < args.len() {
+        if let Some(name) = args[i].strip_prefix("--") {
+            if let Some(value) = args.get(i + 1) {
+                flags.insert(name.to_string(), value.clone());
+                i += 2;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    flags
+}
+
+fn flag_or<T: std::str::FromStr>(flags: &HashMap<String, String>, name: &str, default: T) -> T {
+    flags
+        .get(name)
+        .map(|value| value.parse::<T>().unwrap_or_else(|_| panic!("invalid --{} value", name)))
+        .unwrap_or(default)
+}
+
+fn required_flag<'a>(flags: &'a HashMap<String, String>, name: &str) -> &'a str {
+    flags
+        .get(name)
+        .unwrap_or_else(|| panic!("missing required --{} flag", name))
+}
+
+fn load_event_log(path: &str) -> EventLog {
     let mut options = XESImportOptions::default();
     options.sort_events_with_timestamp_key = Some("time:timestamp".to_string());
-    let mut log1 = import_xes_file(path1, options.clone()).unwrap();
-    let mut log2 = import_xes_file(path2, options).unwrap();
+    let mut log = import_xes_file(path, options).unwrap();
+    log.traces.retain(|trace| !trace.events.is_empty());
+    log
+}
+
+/// `encrypt <eventlog> --out <file>`: derives the activity encoding and sample encryptions the way
+/// organization A would in the real protocol, then runs organization B's
+/// `compute_case_to_trace_using_sample_encryption` over `eventlog` and persists the result to `--out`.
+fn run_encrypt(mut args: Vec<String>) -> std::io::Result<()> {
+    if args.is_empty() {
+        print_usage();
+        return Ok(());
+    }
+    let eventlog_path = args.remove(0);
+    let flags = parse_flags(&args);
+    let out_path = required_flag(&flags, "out");
+    let debug = flag_or(&flags, "debug", true);
 
-    // Filter empty traces
-    log1.traces.retain(|trace| !trace.events.is_empty());
-    log2.traces.retain(|trace| !trace.events.is_empty());
+    let event_log = load_event_log(&eventlog_path);
 
-    println!(
-        "Start directly-follows graph discovery to be output to {}",
-        output_file
+    let mut org_a = PrivateKeyOrganization::new(event_log.clone(), debug);
+    set_server_key(org_a.get_server_key());
+    let activity_to_pos = org_a.update_with_foreign_activities(HashSet::new());
+    let sample_encryptions = org_a.provide_sample_encryptions();
+    let true_val = org_a.encrypt_true();
+
+    let org_b = PublicKeyOrganization::new(event_log.clone(), true_val, debug);
+    let encrypted = org_b.compute_case_to_trace_using_sample_encryption(
+        &activity_to_pos,
+        &event_log,
+        &sample_encryptions,
     );
-    let time_start = Instant::now();
 
-    //setup keys
-    let mut org_a = PrivateKeyOrganization::new(log1, debug);
+    let bytes = bincode::serialize(&encrypted)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    fs::write(out_path, bytes)?;
+    println!("Encrypted {} cases to {}", encrypted.len(), out_path);
+    Ok(())
+}
+
+fn discover_dfg(
+    log_a_path: &str,
+    log_b_path: &str,
+    flags: &HashMap<String, String>,
+) -> (DirectlyFollowsGraph<'static>, HomomorphicCounters) {
+    let debug = flag_or(flags, "debug", false);
+    let use_psi = flag_or(flags, "psi", true);
+    let use_oblivious_accumulation = flag_or(flags, "oblivious", false);
+    let min_edge_frequency = flag_or(flags, "min-freq", 0u64);
+    let chunk_size = flag_or(flags, "chunk-size", 100usize);
+
+    let log_a = load_event_log(log_a_path);
+    let log_b = load_event_log(log_b_path);
+
+    let mut org_a = PrivateKeyOrganization::new(log_a, debug);
     set_server_key(org_a.get_server_key());
     let true_val = org_a.encrypt_true();
+    let mut org_b = PublicKeyOrganization::new(log_b, true_val, debug);
 
-    let mut org_b = PublicKeyOrganization::new(log2, true_val);
+    organization_communication::communicate(
+        &mut org_a,
+        &mut org_b,
+        chunk_size,
+        use_psi,
+        use_oblivious_accumulation,
+        min_edge_frequency,
+        &mut DefaultLogger,
+    )
+}
+
+/// `discover --a <eventlog> --b <eventlog> --out <dfg-file>`: runs the federated protocol end to
+/// end and writes the resulting frequency-annotated DFG to `--out`.
+fn run_discover(args: Vec<String>) -> std::io::Result<()> {
+    let flags = parse_flags(&args);
+    let log_a_path = required_flag(&flags, "a").to_string();
+    let log_b_path = required_flag(&flags, "b").to_string();
+    let out_path = required_flag(&flags, "out").to_string();
+    let report_counters = flag_or(&flags, "report-counters", false);
 
-    let result: DirectlyFollowsGraph =
-        organization_communication::communicate(&mut org_a, &mut org_b, 100, use_psi);
-    let time_elapsed = time_start.elapsed().as_millis();
-    println!("Time elapsed is {}ms", time_elapsed);
+    let time_start = Instant::now();
+    let (graph, counters) = discover_dfg(&log_a_path, &log_b_path, &flags);
+    println!("Time elapsed is {}ms", time_start.elapsed().as_millis());
 
-    // export_dfg_image_png(&result, &output_file.clone().add(".png")).unwrap();
-    let file = File::create(output_file)?;
+    let file = File::create(&out_path)?;
     let mut writer = BufWriter::new(file);
-    writeln!(writer, "{}", result.to_json())?;
+    writeln!(writer, "{}", graph.to_json())?;
     writer.flush()?;
+
+    if report_counters {
+        println!(
+            "Final counters - case ID comparisons: {}, timestamp comparisons: {}, selections: {}",
+            counters.case_id_hom_comparisons,
+            counters.timestamp_hom_comparisons,
+            counters.selection_hom_comparisons
+        );
+    }
+
+    Ok(())
+}
+
+/// `verify --dfg <dfg-file> --a <eventlog> --b <eventlog>`: recomputes the DFG locally with
+/// `--debug true` (the plaintext comparison path, see `PublicKeyOrganization::comparison_fn`) and
+/// cross-checks it against an encrypted DFG a prior `discover` run wrote to `--dfg`, for testing.
+fn run_verify(args: Vec<String>) -> std::io::Result<()> {
+    let flags = parse_flags(&args);
+    let dfg_path = required_flag(&flags, "dfg").to_string();
+    let log_a_path = required_flag(&flags, "a").to_string();
+    let log_b_path = required_flag(&flags, "b").to_string();
+
+    let mut reference_flags = flags.clone();
+    reference_flags.insert("debug".to_string(), "true".to_string());
+    let (reference_graph, _) = discover_dfg(&log_a_path, &log_b_path, &reference_flags);
+
+    let encrypted_json = fs::read_to_string(&dfg_path)?;
+    let encrypted_graph = DirectlyFollowsGraph::from_json(&encrypted_json);
+
+    let mismatches: Vec<String> = reference_graph
+        .directly_follows_relations
+        .iter()
+        .filter_map(|(edge, freq)| match encrypted_graph.directly_follows_relations.get(edge) {
+            Some(other_freq) if other_freq == freq => None,
+            Some(other_freq) => Some(format!(
+                "{:?}: reference={} encrypted={}",
+                edge, freq, other_freq
+            )),
+            None => Some(format!("{:?}: missing from encrypted DFG (reference={})", edge, freq)),
+        })
+        .chain(
+            encrypted_graph
+                .directly_follows_relations
+                .keys()
+                .filter(|edge| !reference_graph.directly_follows_relations.contains_key(*edge))
+                .map(|edge| format!("{:?}: missing from reference DFG", edge)),
+        )
+        .collect();
+
+    if mismatches.is_empty() {
+        println!("verify: OK - encrypted DFG matches the plaintext reference");
+    } else {
+        println!("verify: FAILED - {} mismatching edge(s)", mismatches.len());
+        mismatches.iter().for_each(|mismatch| println!("  {}", mismatch));
+    }
+
     Ok(())
 }